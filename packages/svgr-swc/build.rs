@@ -0,0 +1,46 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+/// Sources whose changes can change the generated code: the passes, the
+/// component template and this crate.
+const SOURCES: [&str; 3] = [
+    "../swc-plugin-svgr/src",
+    "../swc-plugin-transform-svg-component/transform/src",
+    "src",
+];
+
+/// Sets `SVGR_SWC_BUILD_ID` to a hash of `SOURCES`, which the conversion cache
+/// keys on so that entries written by an older build are never reused.
+fn main() {
+    let mut files = vec![];
+    for dir in SOURCES {
+        println!("cargo:rerun-if-changed={}", dir);
+        collect_files(Path::new(dir), &mut files);
+    }
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for file in files {
+        file.hash(&mut hasher);
+        fs::read(&file).unwrap_or_default().hash(&mut hasher);
+    }
+    println!("cargo:rustc-env=SVGR_SWC_BUILD_ID={:016x}", hasher.finish());
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
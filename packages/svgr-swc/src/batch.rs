@@ -0,0 +1,244 @@
+use rayon::prelude::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use swc_plugin_svgr::{transform_svg, Options};
+use transform_svg_component::error::ConfigError;
+
+use crate::{print, state, Config};
+
+/// An SVG file to convert and the name of the component generated from it.
+#[derive(Clone, Debug)]
+pub struct BatchInput {
+    pub path: PathBuf,
+    pub component_name: String,
+}
+
+#[derive(Default)]
+pub struct BatchOptions {
+    /// Directory holding previously generated code, keyed by content hash.
+    /// Caching is disabled when unset.
+    pub cache_dir: Option<PathBuf>,
+    /// Number of worker threads; defaults to the number of cores.
+    pub jobs: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum FileStatus {
+    Converted,
+    Cached,
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct FileReport {
+    pub input: BatchInput,
+    pub status: FileStatus,
+    pub code: Option<String>,
+    /// Config problems the passes recovered from; such results are never cached.
+    pub warnings: Vec<ConfigError>,
+    pub duration: Duration,
+}
+
+#[derive(Debug)]
+pub struct BatchReport {
+    /// One report per input, in input order.
+    pub files: Vec<FileReport>,
+    pub duration: Duration,
+}
+
+impl BatchReport {
+    pub fn count(&self, matches: impl Fn(&FileStatus) -> bool) -> usize {
+        self.files
+            .iter()
+            .filter(|file| matches(&file.status))
+            .count()
+    }
+}
+
+/// Converts every input in parallel, reusing cached output for SVGs whose
+/// bytes and options did not change since the last run.
+pub fn convert_batch(
+    inputs: Vec<BatchInput>,
+    config: &Config,
+    options: &BatchOptions,
+) -> BatchReport {
+    let started = Instant::now();
+
+    if let Some(cache_dir) = &options.cache_dir {
+        // A missing cache directory only disables caching; each lookup then misses.
+        let _ = fs::create_dir_all(cache_dir);
+    }
+
+    let convert_all = || {
+        inputs
+            .into_par_iter()
+            .map(|input| convert_file(input, config, options.cache_dir.as_deref()))
+            .collect::<Vec<FileReport>>()
+    };
+
+    let files = match options.jobs {
+        Some(jobs) => match rayon::ThreadPoolBuilder::new().num_threads(jobs).build() {
+            Ok(pool) => pool.install(convert_all),
+            Err(_) => convert_all(),
+        },
+        None => convert_all(),
+    };
+
+    BatchReport {
+        files,
+        duration: started.elapsed(),
+    }
+}
+
+fn convert_file(input: BatchInput, config: &Config, cache_dir: Option<&Path>) -> FileReport {
+    let started = Instant::now();
+    let report = |input, status, code, warnings| FileReport {
+        input,
+        status,
+        code,
+        warnings,
+        duration: started.elapsed(),
+    };
+
+    let source = match fs::read(&input.path) {
+        Ok(source) => source,
+        Err(err) => return report(input, FileStatus::Failed(err.to_string()), None, vec![]),
+    };
+
+    let options = match config.to_options(state(&input.component_name)) {
+        Ok(options) => options,
+        Err(err) => {
+            let message = format!("invalid config {}", err);
+            return report(input, FileStatus::Failed(message), None, vec![]);
+        }
+    };
+
+    let cache_path = cache_dir.map(|dir| dir.join(format!("{}.js", cache_key(&source, &options))));
+
+    if let Some(code) = cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+    {
+        return report(input, FileStatus::Cached, Some(code), vec![]);
+    }
+
+    let source = match String::from_utf8(source) {
+        Ok(source) => source,
+        Err(_) => {
+            let message = "file is not valid UTF-8".to_string();
+            return report(input, FileStatus::Failed(message), None, vec![]);
+        }
+    };
+
+    match transform_svg(&source, options) {
+        Ok((program, warnings)) => {
+            let code = print(&program);
+            if let (Some(path), true) = (&cache_path, warnings.is_empty()) {
+                // Failing to populate the cache only costs a re-conversion next time.
+                let _ = write_atomically(path, &code);
+            }
+            report(input, FileStatus::Converted, Some(code), warnings)
+        }
+        Err(err) => report(input, FileStatus::Failed(err.to_string()), None, vec![]),
+    }
+}
+
+/// Hashes the SVG bytes together with the serialized options and the build
+/// identifier, a hash of the sources of the passes set by `build.rs`, so that
+/// changing the passes invalidates old entries.
+fn cache_key(source: &[u8], options: &Options) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(env!("SVGR_SWC_BUILD_ID").as_bytes());
+    hasher.update(&serde_json::to_vec(options).unwrap_or_default());
+    hasher.update(&[0]);
+    hasher.update(source);
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Writes `contents` to a temporary file renamed to `path`, so that readers
+/// never see a truncated entry, even when the process is killed mid-write.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    // Unique per write, as several workers may convert identical SVGs.
+    let temp_path = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("svgr-swc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn convert_in_parallel_and_reuse_cache() {
+        let dir = temp_dir("batch");
+        let cache_dir = dir.join("cache");
+        let inputs = (0..8)
+            .map(|i| {
+                let path = dir.join(format!("icon-{}.svg", i));
+                fs::write(&path, format!(r#"<svg><rect width="{}"/></svg>"#, i)).unwrap();
+                BatchInput {
+                    path,
+                    component_name: format!("SvgIcon{}", i),
+                }
+            })
+            .chain(std::iter::once(BatchInput {
+                path: dir.join("missing.svg"),
+                component_name: "SvgMissing".into(),
+            }))
+            .collect::<Vec<_>>();
+        let options = BatchOptions {
+            cache_dir: Some(cache_dir),
+            jobs: Some(2),
+        };
+
+        let first = convert_batch(inputs.clone(), &Config::default(), &options);
+        assert_eq!(
+            first.count(|status| matches!(status, FileStatus::Converted)),
+            8
+        );
+        assert_eq!(
+            first.count(|status| matches!(status, FileStatus::Failed(_))),
+            1
+        );
+        assert!(first.files[3]
+            .code
+            .as_ref()
+            .unwrap()
+            .contains("const SvgIcon3 = (props)=><svg {...props}><rect width=\"3\"/></svg>;"));
+
+        fs::write(&inputs[0].path, r#"<svg><circle r="1"/></svg>"#).unwrap();
+        let second = convert_batch(inputs, &Config::default(), &options);
+        assert!(matches!(second.files[0].status, FileStatus::Converted));
+        assert_eq!(
+            second.count(|status| matches!(status, FileStatus::Cached)),
+            7
+        );
+        assert_eq!(second.files[5].code, first.files[5].code);
+        let temp_files = fs::read_dir(dir.join("cache"))
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().extension().unwrap() != "js")
+            .count();
+        assert_eq!(temp_files, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
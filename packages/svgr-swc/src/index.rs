@@ -0,0 +1,204 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{config::ExportType, Config};
+
+/// A generated component to re-export from the barrel file.
+#[derive(Clone, Debug)]
+pub struct IndexEntry {
+    pub component_name: String,
+    /// Path of the generated file, relative to the directory holding the index.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IndexError {
+    pub component_name: String,
+    pub paths: Vec<PathBuf>,
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<String>>();
+        write!(
+            f,
+            "{} would be exported from more than one file: {}",
+            self.component_name,
+            paths.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+/// Placeholder replaced by the export statements, one per line.
+pub const EXPORTS_PLACEHOLDER: &str = "{{exports}}";
+/// Placeholder replaced by the comma-separated component names.
+pub const NAMES_PLACEHOLDER: &str = "{{names}}";
+
+/// Name of the barrel file, `index.ts` for TypeScript output.
+pub fn index_file_name(config: &Config) -> &'static str {
+    if config.typescript {
+        "index.ts"
+    } else {
+        "index.js"
+    }
+}
+
+/// Generates the barrel file re-exporting every entry under its component
+/// name. Entries are sorted by name so the output does not depend on the
+/// order files were converted in.
+pub fn generate_index(
+    entries: &[IndexEntry],
+    config: &Config,
+    template: Option<&str>,
+) -> Result<String, Vec<IndexError>> {
+    let mut by_name: BTreeMap<&str, Vec<&Path>> = BTreeMap::new();
+    for entry in entries {
+        by_name
+            .entry(&entry.component_name)
+            .or_default()
+            .push(&entry.path);
+    }
+
+    let collisions = by_name
+        .iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(name, paths)| {
+            let mut paths = paths
+                .iter()
+                .map(|path| path.to_path_buf())
+                .collect::<Vec<_>>();
+            paths.sort();
+            IndexError {
+                component_name: name.to_string(),
+                paths,
+            }
+        })
+        .collect::<Vec<IndexError>>();
+    if !collisions.is_empty() {
+        return Err(collisions);
+    }
+
+    let imported = match config.export_type {
+        ExportType::Default => "default",
+        ExportType::Named => config.named_export.as_str(),
+    };
+    let exports = by_name
+        .iter()
+        .map(|(name, paths)| {
+            format!(
+                "export {{ {} as {} }} from \"{}\";",
+                imported,
+                name,
+                import_specifier(paths[0])
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let names = by_name.keys().copied().collect::<Vec<&str>>().join(", ");
+
+    Ok(match template {
+        Some(template) => template
+            .replace(EXPORTS_PLACEHOLDER, &exports)
+            .replace(NAMES_PLACEHOLDER, &names),
+        None => format!("{}\n", exports),
+    })
+}
+
+/// Relative module specifier without extension, always using `/` separators.
+fn import_specifier(path: &Path) -> String {
+    let parts = path
+        .with_extension("")
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            Component::ParentDir => Some("..".into()),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+
+    match parts.first().map(String::as_str) {
+        Some("..") => parts.join("/"),
+        _ => format!("./{}", parts.join("/")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(component_name: &str, path: &str) -> IndexEntry {
+        IndexEntry {
+            component_name: component_name.into(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn generate_sorted_default_exports() {
+        let entries = [
+            entry("SvgClose", "Close.jsx"),
+            entry("SvgArrowLeft", "arrows/ArrowLeft.jsx"),
+        ];
+
+        assert_eq!(
+            generate_index(&entries, &Config::default(), None).unwrap(),
+            r#"export { default as SvgArrowLeft } from "./arrows/ArrowLeft";
+export { default as SvgClose } from "./Close";
+"#
+        );
+    }
+
+    #[test]
+    fn generate_named_exports_with_template() {
+        let config = Config {
+            typescript: true,
+            export_type: ExportType::Named,
+            ..Default::default()
+        };
+        let template = "// @generated\n{{exports}}\nexport const icons = [{{names}}];\n";
+
+        assert_eq!(index_file_name(&config), "index.ts");
+        assert_eq!(
+            generate_index(
+                &[entry("SvgB", "B.tsx"), entry("SvgA", "A.tsx")],
+                &config,
+                Some(template)
+            )
+            .unwrap(),
+            r#"// @generated
+export { ReactComponent as SvgA } from "./A";
+export { ReactComponent as SvgB } from "./B";
+export const icons = [SvgA, SvgB];
+"#
+        );
+    }
+
+    #[test]
+    fn report_component_name_collisions() {
+        let entries = [
+            entry("SvgArrowLeft", "ArrowLeft.jsx"),
+            entry("SvgArrowLeft", "legacy/ArrowLeft.jsx"),
+            entry("SvgClose", "Close.jsx"),
+        ];
+
+        assert_eq!(
+            generate_index(&entries, &Config::default(), None).unwrap_err(),
+            vec![IndexError {
+                component_name: "SvgArrowLeft".into(),
+                paths: vec![
+                    PathBuf::from("ArrowLeft.jsx"),
+                    PathBuf::from("legacy/ArrowLeft.jsx")
+                ],
+            }]
+        );
+    }
+}
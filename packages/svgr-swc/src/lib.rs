@@ -0,0 +1,206 @@
+use std::fmt;
+use swc_core::{
+    common::{sync::Lrc, SourceMap},
+    ecma::{
+        ast::Program,
+        codegen::{text_writer::JsWriter, Emitter},
+    },
+};
+use swc_plugin_svgr::{parse_svg::ParseSvgError, transform_svg};
+use transform_svg_component::{
+    component_name::component_name_from_path,
+    config::{default_state, State},
+    error::ConfigError,
+};
+
+pub use config::Config;
+pub use swc_plugin_svgr::svgr_config as config;
+
+pub mod batch;
+pub mod index;
+
+/// The generated component source, along with the config problems the passes
+/// recovered from while producing it.
+pub struct Conversion {
+    pub code: String,
+    pub errors: Vec<ConfigError>,
+}
+
+#[derive(Debug)]
+pub enum ConvertError {
+    Parse(ParseSvgError),
+    Config(ConfigError),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Parse(err) => write!(f, "{}", err),
+            ConvertError::Config(err) => write!(f, "invalid config {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Converts SVG source into the code of a React component named `component_name`.
+pub fn convert(
+    source: &str,
+    config: &Config,
+    component_name: &str,
+) -> Result<Conversion, ConvertError> {
+    let options = config
+        .to_options(state(component_name))
+        .map_err(ConvertError::Config)?;
+    let (program, errors) = transform_svg(source, options).map_err(ConvertError::Parse)?;
+
+    Ok(Conversion {
+        code: print(&program),
+        errors,
+    })
+}
+
+/// The SVGR state of a component named `component_name`.
+pub(crate) fn state(component_name: &str) -> State {
+    State {
+        component_name: Some(component_name.to_string()),
+        ..default_state()
+    }
+}
+
+/// Derives a component name from a file stem, e.g. `arrow-left` becomes `SvgArrowLeft`.
+pub fn component_name(file_stem: &str) -> String {
+    component_name_from_path(file_stem, "Svg", "")
+}
+
+pub fn print(program: &Program) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+        emitter
+            .emit_program(program)
+            .expect("failed to write generated code to buffer");
+    }
+    String::from_utf8(buf).expect("generated code is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &str = r##"<svg width="88px" height="88px" viewBox="0 0 88 88" version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+    <title>Dismiss</title>
+    <g id="Dismiss" stroke="#063855" stroke-width="2">
+        <path d="M51,37 L37,51" id="Shape"></path>
+    </g>
+</svg>"##;
+
+    #[test]
+    fn convert_with_default_config() {
+        let conversion = convert(SVG, &Config::default(), "SvgDismiss").unwrap();
+
+        assert!(conversion.errors.is_empty());
+        assert_eq!(
+            conversion.code,
+            r##"import * as React from "react";
+const SvgDismiss = (props)=><svg width="88px" height="88px" viewBox="0 0 88 88" xmlns="http://www.w3.org/2000/svg" xmlnsXlink="http://www.w3.org/1999/xlink" {...props}><title>Dismiss</title><g id="Dismiss" stroke="#063855" strokeWidth="2"><path d="M51,37 L37,51" id="Shape"></path></g></svg>;
+export default SvgDismiss;
+"##
+        );
+    }
+
+    #[test]
+    fn convert_typescript_native_icon() {
+        let config = Config {
+            typescript: true,
+            native: true,
+            icon: Some(config::Icon::Enabled(true)),
+            expand_props: Some(config::ExpandProps::Boolean(false)),
+            ..Default::default()
+        };
+
+        let conversion = convert(SVG, &config, "SvgDismiss").unwrap();
+
+        assert_eq!(
+            conversion.code,
+            r##"import * as React from "react";
+import Svg, { G, Path } from "react-native-svg";
+const SvgDismiss = ()=><Svg width={24} height={24} viewBox="0 0 88 88" xmlns="http://www.w3.org/2000/svg" xmlnsXlink="http://www.w3.org/1999/xlink"><G id="Dismiss" stroke="#063855" strokeWidth="2"><Path d="M51,37 L37,51" id="Shape"></Path></G></Svg>;
+export default SvgDismiss;
+"##
+        );
+    }
+
+    #[test]
+    fn convert_with_use_id() {
+        let config = Config {
+            use_id: true,
+            ..Default::default()
+        };
+        let svg = r##"<svg><defs><linearGradient id="a" /></defs><path fill="url(#a)" style="stroke: url(#a)" /></svg>"##;
+
+        let conversion = convert(svg, &config, "SvgStar").unwrap();
+
+        assert_eq!(
+            conversion.code,
+            r##"import * as React from "react";
+import { useId } from "react";
+const SvgStar = (props)=>{
+    const id = useId().replace(/:/g, "");
+    return <svg {...props}><defs><linearGradient id={`${id}-a`}/></defs><path fill={`url(#${id}-a)`} style={{
+        stroke: `url(#${id}-a)`
+    }}/></svg>;
+};
+export default SvgStar;
+"##
+        );
+    }
+
+    #[test]
+    fn report_invalid_svg() {
+        assert!(matches!(
+            convert("<svg>", &Config::default(), "SvgBroken"),
+            Err(ConvertError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn convert_with_color_props() {
+        let config = Config {
+            typescript: true,
+            color_props: Some(config::ColorProps::default()),
+            ..Default::default()
+        };
+        let svg = r##"<svg><path fill="#FFF" stroke="#063855" /><path fill="#063855" style="stop-color: red" /><path fill="#ffffff" stroke="none" /></svg>"##;
+
+        let conversion = convert(svg, &config, "SvgLogo").unwrap();
+
+        assert_eq!(
+            conversion.code,
+            r##"import * as React from "react";
+import { SVGProps } from "react";
+interface SVGRProps {
+    primaryColor?: string;
+    secondaryColor?: string;
+    tertiaryColor?: string;
+}
+const SvgLogo = ({ primaryColor ="#FFF" , secondaryColor ="#063855" , tertiaryColor ="red" , ...props }: SVGProps<SVGSVGElement> & SVGRProps)=><svg {...props}><path fill={primaryColor} stroke={secondaryColor}/><path fill={secondaryColor} style={{
+        stopColor: tertiaryColor
+    }}/><path fill={primaryColor} stroke="none"/></svg>;
+export default SvgLogo;
+"##
+        );
+    }
+
+    #[test]
+    fn derive_component_name_from_file_stem() {
+        assert_eq!(component_name("arrow-left"), "SvgArrowLeft");
+        assert_eq!(component_name("arrow_left_24"), "SvgArrowLeft24");
+    }
+}
@@ -0,0 +1,476 @@
+use clap::{Parser, ValueEnum};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+use svgr_swc::{
+    batch::{convert_batch, BatchInput, BatchOptions, FileStatus},
+    component_name,
+    config::{ColorProps, ExpandProps, ExportType, Icon, JsxRuntime},
+    index::{generate_index, index_file_name, IndexEntry},
+    Config,
+};
+use swc_plugin_svgr::{
+    cleanup_numeric_values::Options as CleanupNumericValuesOptions,
+    convert_shapes::Options as ConvertShapesOptions, css_variables::Options as CssVariablesOptions,
+    current_color::Options as CurrentColorOptions,
+    minify_path_data::Options as MinifyPathDataOptions, prefix_ids::Options as PrefixIdsOptions,
+    remove_unused_ids::Options as RemoveUnusedIdsOptions,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExpandPropsArg {
+    Start,
+    End,
+    None,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportTypeArg {
+    Default,
+    Named,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum JsxRuntimeArg {
+    Classic,
+    ClassicPreact,
+    Automatic,
+}
+
+/// Transform SVG files into React components.
+#[derive(Parser)]
+#[command(name = "svgr-swc", version)]
+struct Cli {
+    /// SVG files or directories to convert
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Output directory; a single file is written to stdout when omitted
+    #[arg(short = 'd', long)]
+    out_dir: Option<PathBuf>,
+
+    /// Extension of the generated files (defaults to "jsx", or "tsx" with --typescript)
+    #[arg(long)]
+    ext: Option<String>,
+
+    /// Reuse output of unchanged SVGs from this directory across runs
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Number of files converted in parallel (defaults to the number of cores)
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+
+    /// Generate an index file re-exporting every component in --out-dir
+    #[arg(long, requires = "out_dir")]
+    index: bool,
+
+    /// Template for the index file; "{{exports}}" is replaced by the export
+    /// statements and "{{names}}" by the comma-separated component names
+    #[arg(long, value_name = "FILE", requires = "index")]
+    index_template: Option<PathBuf>,
+
+    /// Generate TypeScript components
+    #[arg(long)]
+    typescript: bool,
+
+    /// Generate React Native components using react-native-svg
+    #[arg(long)]
+    native: bool,
+
+    /// Forward ref to the root SVG element
+    #[arg(long = "ref")]
+    forward_ref: bool,
+
+    /// Wrap the component in React.memo
+    #[arg(long)]
+    memo: bool,
+
+    /// Use 1em (or the given size) as SVG width and height
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", value_name = "SIZE")]
+    icon: Option<String>,
+
+    /// Remove width and height from the root SVG element
+    #[arg(long)]
+    no_dimensions: bool,
+
+    /// Where to spread props on the root SVG element
+    #[arg(long, value_enum, default_value = "end")]
+    expand_props: ExpandPropsArg,
+
+    /// Add a title prop rendering a <title> element
+    #[arg(long)]
+    title_prop: bool,
+
+    /// Add a desc prop rendering a <desc> element
+    #[arg(long)]
+    desc_prop: bool,
+
+    /// Export the component as default or named export
+    #[arg(long, value_enum, default_value = "default")]
+    export_type: ExportTypeArg,
+
+    /// Name of the export when using a named export
+    #[arg(long, default_value = "ReactComponent")]
+    named_export: String,
+
+    /// JSX runtime the generated code targets
+    #[arg(long, value_enum, default_value = "classic")]
+    jsx_runtime: JsxRuntimeArg,
+
+    /// Add a prop to the root SVG element, e.g. --svg-props focusable=false
+    #[arg(long = "svg-props", value_name = "NAME=VALUE", value_parser = parse_key_value)]
+    svg_props: Vec<(String, String)>,
+
+    /// Replace an attribute value, e.g. --replace-attr-values "#063855={props.color}"
+    #[arg(long = "replace-attr-values", value_name = "OLD=NEW", value_parser = parse_key_value)]
+    replace_attr_values: Vec<(String, String)>,
+
+    /// Make ids unique to each component instance with React's useId
+    #[arg(long)]
+    use_id: bool,
+
+    /// Turn each color into a prop defaulting to it, named primaryColor, secondaryColor, ... by frequency
+    #[arg(long)]
+    color_props: bool,
+
+    /// Name the prop of a color with --color-props, e.g. --color-prop-name "#063855=brandColor"
+    #[arg(long, value_name = "COLOR=NAME", value_parser = parse_key_value, requires = "color_props")]
+    color_prop_name: Vec<(String, String)>,
+
+    /// Remove editor metadata, generator descriptions and unused xmlns declarations
+    #[arg(long)]
+    cleanup: bool,
+
+    /// Inline the rules of <style> elements into the elements they match
+    #[arg(long)]
+    inline_styles: bool,
+
+    /// Remove elements that never render, such as hidden layers and empty groups
+    #[arg(long)]
+    remove_hidden_elements: bool,
+
+    /// Remove ids nothing refers to, and the unused definitions holding them
+    #[arg(long)]
+    remove_unused_ids: bool,
+
+    /// Keep the ids matching PATTERN, a regular expression, with --remove-unused-ids
+    #[arg(long, value_name = "PATTERN", requires = "remove_unused_ids")]
+    keep_ids: Vec<String>,
+
+    /// Prefix ids and their references with PREFIX, where {componentName} is the component name
+    #[arg(long, num_args = 0..=1, default_missing_value = "{componentName}-", value_name = "PREFIX")]
+    prefix_ids: Option<String>,
+
+    /// Convert rects (rounded ones included), circles, ellipses, lines, polylines and polygons to paths, keeping DECIMALS decimals (defaults to 3)
+    #[arg(long, num_args = 0..=1, default_missing_value = "3", value_name = "DECIMALS")]
+    convert_shapes: Option<u32>,
+
+    /// Rewrite path data in its shortest form, keeping DECIMALS decimals (defaults to 3)
+    #[arg(long, num_args = 0..=1, default_missing_value = "3", value_name = "DECIMALS")]
+    minify_path_data: Option<u32>,
+
+    /// Round geometry attributes and style numbers to DECIMALS decimals (defaults to 3)
+    #[arg(long, num_args = 0..=1, default_missing_value = "3", value_name = "DECIMALS")]
+    cleanup_numeric_values: Option<u32>,
+
+    /// Collapse groups without effect and merge adjacent paths with the same attributes
+    #[arg(long)]
+    collapse_groups: bool,
+
+    /// Replace fill, stroke and stop-color colors with currentColor
+    #[arg(long)]
+    current_color: bool,
+
+    /// Keep COLOR with --current-color
+    #[arg(long, value_name = "COLOR", requires = "current_color")]
+    keep_colors: Vec<String>,
+
+    /// Rewrite colors to CSS variables named --PREFIX-color-N, where {componentName} is the kebab-cased component name
+    #[arg(long, num_args = 0..=1, default_missing_value = "{componentName}", value_name = "PREFIX")]
+    css_variables: Option<String>,
+}
+
+impl Cli {
+    fn config(&self) -> Config {
+        Config {
+            typescript: self.typescript,
+            native: self.native,
+            forward_ref: self.forward_ref,
+            memo: self.memo,
+            icon: Some(match self.icon.as_deref() {
+                None | Some("false") => Icon::Enabled(false),
+                Some("true") => Icon::Enabled(true),
+                Some(size) => size
+                    .parse::<f64>()
+                    .map_or_else(|_| Icon::Value(size.to_string()), Icon::Size),
+            }),
+            dimensions: !self.no_dimensions,
+            expand_props: Some(match self.expand_props {
+                ExpandPropsArg::Start => ExpandProps::Start,
+                ExpandPropsArg::End => ExpandProps::End,
+                ExpandPropsArg::None => ExpandProps::Boolean(false),
+            }),
+            title_prop: self.title_prop,
+            desc_prop: self.desc_prop,
+            export_type: match self.export_type {
+                ExportTypeArg::Default => ExportType::Default,
+                ExportTypeArg::Named => ExportType::Named,
+            },
+            named_export: self.named_export.clone(),
+            jsx_runtime: Some(match self.jsx_runtime {
+                JsxRuntimeArg::Classic => JsxRuntime::Classic,
+                JsxRuntimeArg::ClassicPreact => JsxRuntime::ClassicPreact,
+                JsxRuntimeArg::Automatic => JsxRuntime::Automatic,
+            }),
+            jsx_runtime_import: None,
+            svg_props: self.svg_props.clone(),
+            replace_attr_values: self.replace_attr_values.clone(),
+            use_id: self.use_id,
+            color_props: self.color_props.then(|| ColorProps {
+                colors: self.color_prop_name.iter().cloned().collect(),
+                ..Default::default()
+            }),
+            cleanup: self.cleanup.then(Default::default),
+            inline_styles: self.inline_styles,
+            remove_hidden_elements: self.remove_hidden_elements,
+            remove_unused_ids: self.remove_unused_ids.then(|| RemoveUnusedIdsOptions {
+                keep: self.keep_ids.clone(),
+            }),
+            prefix_ids: self
+                .prefix_ids
+                .clone()
+                .map(|prefix| PrefixIdsOptions { prefix }),
+            convert_shapes: self
+                .convert_shapes
+                .map(|precision| ConvertShapesOptions { precision }),
+            minify_path_data: self
+                .minify_path_data
+                .map(|precision| MinifyPathDataOptions { precision }),
+            cleanup_numeric_values: self
+                .cleanup_numeric_values
+                .map(|precision| CleanupNumericValuesOptions { precision }),
+            collapse_groups: self.collapse_groups.then(Default::default),
+            current_color: self.current_color.then(|| CurrentColorOptions {
+                keep: self.keep_colors.clone(),
+            }),
+            css_variables: self
+                .css_variables
+                .clone()
+                .map(|prefix| CssVariablesOptions { prefix }),
+        }
+    }
+}
+
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=VALUE, found \"{}\"", arg))
+}
+
+/// Collects `.svg` files, keeping each file's path relative to its input root.
+fn collect_svg_files(
+    input: &Path,
+    relative: &Path,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+) -> std::io::Result<()> {
+    if input.is_dir() {
+        let mut entries = fs::read_dir(input)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        entries.sort();
+
+        for entry in entries {
+            let name = entry.file_name().map(PathBuf::from).unwrap_or_default();
+            collect_svg_files(&entry, &relative.join(name), files)?;
+        }
+    } else if input.extension().is_some_and(|ext| ext == "svg") {
+        files.push((input.to_path_buf(), relative.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+/// Output file of a component, e.g. `icons/arrow-left.svg` becomes `icons/ArrowLeft.jsx`.
+fn output_path(relative: &Path, component_name: &str, ext: &str) -> PathBuf {
+    let name = component_name.strip_prefix("Svg").unwrap_or(component_name);
+    relative.with_file_name(format!("{}.{}", name, ext))
+}
+
+/// Output files several inputs would be written to, e.g. both `arrow-left.svg`
+/// and `arrow_left.svg` give `ArrowLeft.jsx`.
+fn output_collisions(files: &[(PathBuf, PathBuf)], entries: &[IndexEntry]) -> Vec<String> {
+    let mut by_output: BTreeMap<&Path, Vec<String>> = BTreeMap::new();
+    for ((path, _), entry) in files.iter().zip(entries) {
+        by_output
+            .entry(&entry.path)
+            .or_default()
+            .push(path.display().to_string());
+    }
+
+    by_output
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(output, paths)| {
+            format!(
+                "{} would be generated from more than one file: {}",
+                output.display(),
+                paths.join(", ")
+            )
+        })
+        .collect()
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = cli.config();
+    let ext = cli
+        .ext
+        .clone()
+        .unwrap_or_else(|| if cli.typescript { "tsx" } else { "jsx" }.to_string());
+
+    let mut files = vec![];
+    for input in &cli.inputs {
+        let relative = if input.is_dir() {
+            PathBuf::new()
+        } else {
+            input.file_name().map(PathBuf::from).unwrap_or_default()
+        };
+        if let Err(err) = collect_svg_files(input, &relative, &mut files) {
+            eprintln!("error: failed to read {}: {}", input.display(), err);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if cli.out_dir.is_none() && files.len() != 1 {
+        eprintln!("error: --out-dir is required when converting more than one file");
+        return ExitCode::FAILURE;
+    }
+
+    let inputs = files
+        .iter()
+        .map(|(path, _)| BatchInput {
+            path: path.clone(),
+            component_name: component_name(&path.file_stem().unwrap_or_default().to_string_lossy()),
+        })
+        .collect::<Vec<BatchInput>>();
+    let index_entries = inputs
+        .iter()
+        .zip(&files)
+        .map(|(input, (_, relative))| IndexEntry {
+            component_name: input.component_name.clone(),
+            path: output_path(relative, &input.component_name, &ext),
+        })
+        .collect::<Vec<IndexEntry>>();
+
+    let index_template = match &cli.index_template {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(template) => Some(template),
+            Err(err) => {
+                eprintln!("error: failed to read {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    if cli.out_dir.is_some() {
+        // Files sharing an output path would overwrite each other's output, so
+        // bail out before writing. With an index, component names must be
+        // unique too, which covers output paths.
+        let collisions = if cli.index {
+            match generate_index(&index_entries, &config, None) {
+                Ok(_) => vec![],
+                Err(collisions) => collisions.iter().map(ToString::to_string).collect(),
+            }
+        } else {
+            output_collisions(&files, &index_entries)
+        };
+        if !collisions.is_empty() {
+            for collision in collisions {
+                eprintln!("error: {}", collision);
+            }
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let report = convert_batch(
+        inputs,
+        &config,
+        &BatchOptions {
+            cache_dir: cli.cache_dir.clone(),
+            jobs: cli.jobs,
+        },
+    );
+
+    let mut failed = 0;
+    let mut written_entries = vec![];
+    for ((file, (path, _)), entry) in report.files.iter().zip(&files).zip(&index_entries) {
+        let code = match (&file.status, &file.code) {
+            (FileStatus::Converted | FileStatus::Cached, Some(code)) => code,
+            (FileStatus::Failed(err), _) => {
+                eprintln!("error: {}: {}", path.display(), err);
+                failed += 1;
+                continue;
+            }
+            (_, None) => continue,
+        };
+
+        for err in &file.warnings {
+            eprintln!("warning: {}: {}", path.display(), err);
+        }
+
+        match &cli.out_dir {
+            Some(out_dir) => {
+                let out_path = out_dir.join(&entry.path);
+                let written = out_path
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|_| fs::write(&out_path, code));
+
+                match written {
+                    Ok(_) => {
+                        eprintln!("{} -> {}", path.display(), out_path.display());
+                        written_entries.push(entry.clone());
+                    }
+                    Err(err) => {
+                        eprintln!("error: failed to write {}: {}", out_path.display(), err);
+                        failed += 1;
+                    }
+                }
+            }
+            None => print!("{}", code),
+        }
+    }
+
+    if let (Some(out_dir), true) = (&cli.out_dir, cli.index) {
+        let index_path = out_dir.join(index_file_name(&config));
+        // Only entries checked for collisions before converting can end up here.
+        if let Ok(index) = generate_index(&written_entries, &config, index_template.as_deref()) {
+            if let Err(err) = fs::write(&index_path, index) {
+                eprintln!("error: failed to write {}: {}", index_path.display(), err);
+                failed += 1;
+            }
+        }
+    }
+
+    if cli.out_dir.is_some() {
+        eprintln!(
+            "{} converted, {} cached, {} failed in {:.2?}",
+            report.count(|status| matches!(status, FileStatus::Converted)),
+            report.count(|status| matches!(status, FileStatus::Cached)),
+            failed,
+            report.duration
+        );
+    }
+
+    if failed > 0 {
+        eprintln!("{} of {} files failed", failed, files.len());
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,208 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use swc_core::ecma::{ast::Program, visit::VisitMutWith};
+use swc_plugin_svgr::{
+    add_jsx_attribute::AddJSXAttributeVisitor,
+    parse_svg::parse_svg,
+    remove_jsx_attribute::RemoveJSXAttributeVisitor,
+    remove_jsx_empty_expression::RemoveEmptyExpressionVisitor,
+    replace_jsx_attribute_value::ReplaceJSXAttributeValueVisitor,
+    svg_dynamic_title::{DynamicTitleVisitor, Options as DynamicTitleOptions},
+    svg_em_dimensions::SVGEmDimensionsVisitor,
+    transform,
+    transform_attribute::TransformAttributeVisitor,
+    transform_react_native_svg::TransformReactNativeSVGVisitor,
+    Options,
+};
+use transform_svg_component::{error::parse_config, TransformSVGComponentVisitor};
+
+const CORPUS_SIZE: usize = 5000;
+
+/// Deterministic pseudo-random numbers, so every run uses the same corpus.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+fn attributes(rng: &mut Lcg) -> String {
+    const COLORS: [&str; 5] = ["#063855", "#fff", "red", "currentColor", "none"];
+    let mut attributes = vec![];
+    if rng.next(2) == 0 {
+        attributes.push(format!("fill=\"{}\"", COLORS[rng.next(5) as usize]));
+    }
+    if rng.next(3) == 0 {
+        attributes.push(format!(
+            "stroke=\"{}\" stroke-width=\"{}\"",
+            COLORS[rng.next(5) as usize],
+            rng.next(3) + 1
+        ));
+    }
+    if rng.next(5) == 0 {
+        attributes.push("class=\"icon\" style=\"opacity: 0.5;fill-rule: evenodd\"".into());
+    }
+    if rng.next(10) == 0 {
+        attributes.push("data-name=\"Layer 1\" aria-hidden=\"true\"".into());
+    }
+    attributes.join(" ")
+}
+
+fn shape(rng: &mut Lcg, depth: usize) -> String {
+    match rng.next(10) {
+        0 | 1 if depth < 3 => {
+            let children = (0..rng.next(3) + 1)
+                .map(|_| shape(rng, depth + 1))
+                .collect::<String>();
+            format!("<g {} transform=\"translate(1 1)\">{}</g>", attributes(rng), children)
+        }
+        2 => format!("<circle cx=\"12\" cy=\"12\" r=\"{}\" {}/>", rng.next(10) + 1, attributes(rng)),
+        3 => format!("<rect x=\"1\" y=\"2\" width=\"10\" height=\"4\" rx=\"1\" {}/>", attributes(rng)),
+        4 => "<defs><linearGradient id=\"g\"><stop offset=\"0\" stop-color=\"#fff\"/></linearGradient></defs>".into(),
+        5 => "<filter id=\"f\"><feGaussianBlur stdDeviation=\"2\"/></filter>".into(),
+        _ => format!(
+            "<path d=\"M{} {}L{}.5 {}.25z\" {}/>",
+            rng.next(24),
+            rng.next(24),
+            rng.next(24),
+            rng.next(24),
+            attributes(rng)
+        ),
+    }
+}
+
+fn corpus() -> Vec<Program> {
+    let mut rng = Lcg(7);
+    (0..CORPUS_SIZE)
+        .map(|i| {
+            let title = if rng.next(3) == 0 {
+                format!("<title>Icon {}</title>", i)
+            } else {
+                String::new()
+            };
+            let body = (0..rng.next(8) + 1)
+                .map(|_| shape(&mut rng, 0))
+                .collect::<String>();
+            let source = format!(
+                "<svg width=\"24px\" height=\"24px\" viewBox=\"0 0 24 24\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">{}{}</svg>",
+                title, body
+            );
+            parse_svg(&source).expect("corpus icons are valid SVG")
+        })
+        .collect()
+}
+
+fn options(native: bool) -> Options {
+    let config = serde_json::json!({
+        "transform_svg_component": {
+            "typescript": true,
+            "titleProp": true,
+            "descProp": true,
+            "expandProps": "end",
+            "ref": true,
+            "native": native,
+            "state": { "componentName": "SvgIcon" },
+        },
+        "em_dimensions": {},
+        "remove_jsx_attribute": { "elements": ["svg", "Svg"], "attributes": ["version"] },
+        "add_jsx_attribute": {
+            "elements": ["svg", "Svg"],
+            "attributes": [
+                { "name": "ref", "value": "ref", "literal": true },
+                { "name": "aria-labelledby", "value": "titleId", "literal": true },
+                { "name": "props", "spread": true, "position": "end" },
+            ],
+        },
+        "replace_attribute_values": {
+            "values": [{ "value": "#063855", "newValue": "props.color", "literal": true }],
+        },
+        "title_prop": true,
+        "desc_prop": true,
+        "native": native,
+    });
+    parse_config(&config.to_string()).expect("benchmark options are valid")
+}
+
+/// Runs the same visitors as `transform`, each over the whole program, one
+/// after another. This measures the cost of the separate traversals alone,
+/// not the speedup over the implementation `transform` replaced. Only timings
+/// are compared here; the registry tests check both ways give the same code.
+fn transform_in_separate_traversals(mut program: Program, options: Options) -> Program {
+    if let Some(config) = options.transform_svg_component {
+        program.visit_mut_with(&mut TransformSVGComponentVisitor::new(config));
+    }
+    if let Some(em_dimensions) = options.em_dimensions {
+        program.visit_mut_with(&mut SVGEmDimensionsVisitor::new(em_dimensions));
+    }
+    if let Some(remove_jsx_attribute) = options.remove_jsx_attribute {
+        program.visit_mut_with(&mut RemoveJSXAttributeVisitor::new(remove_jsx_attribute));
+    }
+    if let Some(add_jsx_attribute) = options.add_jsx_attribute {
+        program.visit_mut_with(&mut AddJSXAttributeVisitor::new(add_jsx_attribute));
+    }
+    program.visit_mut_with(&mut RemoveEmptyExpressionVisitor);
+    if let Some(replace_attribute_values) = options.replace_attribute_values {
+        program.visit_mut_with(&mut ReplaceJSXAttributeValueVisitor::new(
+            replace_attribute_values,
+        ));
+    }
+    if options.title_prop {
+        program.visit_mut_with(&mut DynamicTitleVisitor::new(DynamicTitleOptions {
+            tag: "title".into(),
+        }));
+    }
+    if options.desc_prop {
+        program.visit_mut_with(&mut DynamicTitleVisitor::new(DynamicTitleOptions {
+            tag: "desc".into(),
+        }));
+    }
+    if options.native {
+        program.visit_mut_with(&mut TransformReactNativeSVGVisitor { comments: None });
+    }
+    program.visit_mut_with(&mut TransformAttributeVisitor);
+    program
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let corpus = corpus();
+
+    for native in [false, true] {
+        let options = options(native);
+        let mut group = c.benchmark_group(if native { "native" } else { "web" });
+        group.throughput(Throughput::Elements(CORPUS_SIZE as u64));
+        group.sample_size(20);
+
+        group.bench_function("separate_traversals", |b| {
+            b.iter_batched(
+                || corpus.clone(),
+                |corpus| {
+                    for program in corpus {
+                        transform_in_separate_traversals(program, options.clone());
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function("single_traversal", |b| {
+            b.iter_batched(
+                || corpus.clone(),
+                |corpus| {
+                    for program in corpus {
+                        transform(program, options.clone(), None);
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_pipeline);
+criterion_main!(benches);
@@ -1,22 +1,27 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use swc_core::{
-    common::{input::StringInput, BytePos, DUMMY_SP},
+    common::DUMMY_SP,
     ecma::{
         ast::*,
-        parser::{EsConfig, Parser, Syntax},
         visit::{VisitMut, VisitMutWith},
     },
 };
+use transform_svg_component::error::ConfigError;
 
-#[derive(Deserialize)]
+use crate::{
+    expression::parse_expression,
+    pipeline::{is_element_of, ElementPass},
+};
+
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "lowercase")]
 enum Position {
     Start,
     End,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 enum Value {
     Boolean(bool),
@@ -25,7 +30,7 @@ enum Value {
     Null,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 struct AttributeOption {
     name: String,
     value: Option<Value>,
@@ -34,7 +39,7 @@ struct AttributeOption {
     position: Option<Position>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
     elements: Vec<String>,
     attributes: Vec<AttributeOption>,
@@ -42,60 +47,103 @@ pub struct Options {
 
 pub struct AddJSXAttributeVisitor {
     pub options: Options,
+    pub errors: Vec<ConfigError>,
+    /// Built once from `options.attributes`, in the same order.
+    attributes: Vec<Result<JSXAttrOrSpread, ConfigError>>,
 }
 
-impl VisitMut for AddJSXAttributeVisitor {
-    fn visit_mut_jsx_opening_element(&mut self, jsx_opening_element: &mut JSXOpeningElement) {
-        jsx_opening_element.visit_mut_children_with(self);
+impl AddJSXAttributeVisitor {
+    pub fn new(options: Options) -> Self {
+        let attributes = options
+            .attributes
+            .iter()
+            .enumerate()
+            .map(|(index, attribute_option)| {
+                get_attribute(attribute_option)
+                    .map_err(|err| err.nested(&format!("attributes[{}]", index)))
+            })
+            .collect();
+
+        AddJSXAttributeVisitor {
+            options,
+            errors: vec![],
+            attributes,
+        }
+    }
+}
 
-        if let JSXElementName::Ident(Ident { sym, .. }) = &jsx_opening_element.name {
-            if self.options.elements.contains(&sym.to_string()) {
-                for attribute_option in &self.options.attributes {
-                    let new_attribute = get_attribute(attribute_option);
+impl ElementPass for AddJSXAttributeVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        if !is_element_of(jsx_element, &self.options.elements) {
+            return;
+        }
 
-                    if let Some(index) = jsx_opening_element
-                        .attrs
-                        .iter()
-                        .position(|x| is_equal_with_new_attribute(x.clone(), attribute_option))
-                    {
-                        jsx_opening_element.attrs[index] = new_attribute;
-                    } else {
-                        match attribute_option.position {
-                            Some(Position::End) | None => {
-                                jsx_opening_element.attrs.push(new_attribute);
-                            }
-                            Some(Position::Start) => {
-                                jsx_opening_element.attrs.insert(0, new_attribute);
-                            }
-                        }
+        let jsx_opening_element = &mut jsx_element.opening;
+        for (attribute_option, new_attribute) in
+            self.options.attributes.iter().zip(&self.attributes)
+        {
+            let new_attribute = match new_attribute {
+                Ok(attribute) => attribute.clone(),
+                Err(err) => {
+                    if !self.errors.contains(err) {
+                        self.errors.push(err.clone());
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(index) = jsx_opening_element
+                .attrs
+                .iter()
+                .position(|x| is_equal_with_new_attribute(x, attribute_option))
+            {
+                jsx_opening_element.attrs[index] = new_attribute;
+            } else {
+                match attribute_option.position {
+                    Some(Position::End) | None => {
+                        jsx_opening_element.attrs.push(new_attribute);
+                    }
+                    Some(Position::Start) => {
+                        jsx_opening_element.attrs.insert(0, new_attribute);
                     }
                 }
             }
         }
     }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
 }
 
-fn get_attribute(attribute_option: &AttributeOption) -> JSXAttrOrSpread {
+impl VisitMut for AddJSXAttributeVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+fn get_attribute(attribute_option: &AttributeOption) -> Result<JSXAttrOrSpread, ConfigError> {
     let AttributeOption { name, spread, .. } = attribute_option;
 
     if let Some(is_spread) = spread {
         if *is_spread {
-            return JSXAttrOrSpread::SpreadElement(SpreadElement {
+            return Ok(JSXAttrOrSpread::SpreadElement(SpreadElement {
                 dot3_token: DUMMY_SP,
                 expr: Box::new(Expr::Ident(Ident::new(name.to_string().into(), DUMMY_SP))),
-            });
+            }));
         }
     }
 
-    JSXAttrOrSpread::JSXAttr(JSXAttr {
+    Ok(JSXAttrOrSpread::JSXAttr(JSXAttr {
         span: DUMMY_SP,
         name: JSXAttrName::Ident(Ident::new(name.to_string().into(), DUMMY_SP)),
-        value: get_attribute_value(attribute_option),
-    })
+        value: get_attribute_value(attribute_option)?,
+    }))
 }
 
-fn get_attribute_value(option: &AttributeOption) -> Option<JSXAttrValue> {
-    if let Some(value) = &option.value {
+fn get_attribute_value(option: &AttributeOption) -> Result<Option<JSXAttrValue>, ConfigError> {
+    Ok(if let Some(value) = &option.value {
         match value {
             Value::Boolean(bool_value) => Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
                 span: DUMMY_SP,
@@ -120,37 +168,18 @@ fn get_attribute_value(option: &AttributeOption) -> Option<JSXAttrValue> {
                 })));
                 if let Some(literal) = option.literal {
                     if literal {
-                        let mut parser = Parser::new(
-                            Syntax::Es(EsConfig {
-                                jsx: false,
-                                fn_bind: false,
-                                decorators: false,
-                                decorators_before_export: false,
-                                export_default_from: true,
-                                import_assertions: false,
-                                allow_return_outside_function: false,
-                                allow_super_outside_method: false,
-                                auto_accessors: false,
-                            }),
-                            StringInput::new(string_value, BytePos(0), BytePos(0)),
-                            None,
-                        );
+                        let expr = parse_expression(string_value).map_err(|_| {
+                            ConfigError::new("value", "failed to parse attribute value expression")
+                                .with_value(format!("\"{}\"", string_value))
+                                .with_hint("a literal value must be a valid JavaScript expression, or set `literal: false`")
+                        })?;
 
-                        let ast = parser.parse_module().unwrap_or_else(|_| {
-                            panic!(
-                                "Failed to parse attribute value \"{}\" expression",
-                                string_value
-                            )
-                        });
-
-                        if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &ast.body[0] {
-                            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        expr.map(|expr| {
+                            JSXAttrValue::JSXExprContainer(JSXExprContainer {
                                 span: DUMMY_SP,
-                                expr: JSXExpr::Expr(expr.clone()),
-                            }))
-                        } else {
-                            None
-                        }
+                                expr: JSXExpr::Expr(expr),
+                            })
+                        })
                     } else {
                         str_lit_attribute
                     }
@@ -162,41 +191,36 @@ fn get_attribute_value(option: &AttributeOption) -> Option<JSXAttrValue> {
         }
     } else {
         None
-    }
+    })
 }
 
 fn is_equal_with_new_attribute(
-    attribute: JSXAttrOrSpread,
+    attribute: &JSXAttrOrSpread,
     attribute_option: &AttributeOption,
 ) -> bool {
-    let mut is_equal_attribute = false;
-
-    if let Some(spread) = attribute_option.spread {
-        if spread {
-            if let JSXAttrOrSpread::SpreadElement(SpreadElement { expr, .. }) = attribute {
-                if let Expr::Ident(Ident { sym, .. }) = *expr {
-                    is_equal_attribute = sym == attribute_option.name;
-                }
-            }
+    match attribute {
+        JSXAttrOrSpread::SpreadElement(SpreadElement { expr, .. }) => {
+            attribute_option.spread == Some(true)
+                && matches!(&**expr, Expr::Ident(Ident { sym, .. }) if *sym == *attribute_option.name)
         }
-    } else if let JSXAttrOrSpread::JSXAttr(JSXAttr {
-        name: JSXAttrName::Ident(Ident { sym, .. }),
-        ..
-    }) = attribute
-    {
-        is_equal_attribute = sym == attribute_option.name;
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            ..
+        }) => attribute_option.spread.is_none() && *sym == *attribute_option.name,
+        _ => false,
     }
-
-    is_equal_attribute
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use swc_core::ecma::{
-        parser::{EsConfig, Syntax},
-        transforms::testing::test,
-        visit::as_folder,
+    use swc_core::{
+        common::{input::StringInput, BytePos},
+        ecma::{
+            parser::{EsConfig, Parser, Syntax},
+            transforms::testing::test,
+            visit::as_folder,
+        },
     };
 
     test!(
@@ -204,18 +228,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "disabled".into(),
-                    spread: None,
-                    literal: None,
-                    value: None,
-                    position: None,
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "disabled".into(),
+                spread: None,
+                literal: None,
+                value: None,
+                position: None,
+            }]
+        })),
         add_simple_attribute,
         // Input codes
         r#"<div />"#,
@@ -227,18 +249,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "disabled".into(),
-                    spread: None,
-                    literal: None,
-                    value: Some(Value::String("true".into())),
-                    position: None,
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "disabled".into(),
+                spread: None,
+                literal: None,
+                value: Some(Value::String("true".into())),
+                position: None,
+            }]
+        })),
         add_attribute_with_value,
         // Input codes
         r#"<div />"#,
@@ -250,18 +270,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "ref".into(),
-                    spread: None,
-                    literal: Some(true),
-                    value: Some(Value::String("ref".into())),
-                    position: None,
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "ref".into(),
+                spread: None,
+                literal: Some(true),
+                value: Some(Value::String("ref".into())),
+                position: None,
+            }]
+        })),
         add_literal_attribute,
         // Input codes
         r#"<div />"#,
@@ -273,18 +291,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "props".into(),
-                    spread: Some(true),
-                    literal: None,
-                    value: None,
-                    position: Some(Position::Start),
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "props".into(),
+                spread: Some(true),
+                literal: None,
+                value: None,
+                position: Some(Position::Start),
+            }]
+        })),
         add_spread_attribute_start,
         // Input codes
         r#"<div foo><span /></div>"#,
@@ -296,18 +312,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["span".into()],
-                attributes: vec![AttributeOption {
-                    name: "props".into(),
-                    spread: Some(true),
-                    literal: None,
-                    value: None,
-                    position: Some(Position::End),
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["span".into()],
+            attributes: vec![AttributeOption {
+                name: "props".into(),
+                spread: Some(true),
+                literal: None,
+                value: None,
+                position: Some(Position::End),
+            }]
+        })),
         add_spread_attribute_end,
         // Input codes
         r#"<div><span foo="bar" /></div>"#,
@@ -319,18 +333,16 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "disabled".into(),
-                    spread: None,
-                    literal: None,
-                    value: Some(Value::Boolean(false)),
-                    position: None,
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "disabled".into(),
+                spread: None,
+                literal: None,
+                value: Some(Value::Boolean(false)),
+                position: None,
+            }]
+        })),
         replace_attribute,
         // Input codes
         r#"<div disabled />"#,
@@ -343,20 +355,57 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(AddJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["div".into()],
-                attributes: vec![AttributeOption {
-                    name: "aria-describedby".into(),
-                    spread: None,
-                    literal: Some(true),
-                    value: Some(Value::String("titleId".into())),
-                    position: None,
-                }]
-            }
-        }),
+        |_| as_folder(AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![AttributeOption {
+                name: "aria-describedby".into(),
+                spread: None,
+                literal: Some(true),
+                value: Some(Value::String("titleId".into())),
+                position: None,
+            }]
+        })),
         add_aria_attribute,
         r#"<div />"#,
         r#"<div aria-describedby={titleId} />;"#
     );
+
+    #[test]
+    fn collect_error_for_invalid_literal_and_keep_going() {
+        let mut visitor = AddJSXAttributeVisitor::new(Options {
+            elements: vec!["div".into()],
+            attributes: vec![
+                AttributeOption {
+                    name: "ref".into(),
+                    spread: None,
+                    literal: Some(true),
+                    value: Some(Value::String("{".into())),
+                    position: None,
+                },
+                AttributeOption {
+                    name: "disabled".into(),
+                    spread: None,
+                    literal: None,
+                    value: None,
+                    position: None,
+                },
+            ],
+        });
+        let mut parser = Parser::new(
+            Syntax::Es(EsConfig {
+                jsx: true,
+                ..Default::default()
+            }),
+            StringInput::new("<div />", BytePos(0), BytePos(0)),
+            None,
+        );
+        let mut expr = parser.parse_expr().unwrap();
+
+        expr.visit_mut_with(&mut visitor);
+
+        assert_eq!(visitor.errors.len(), 1);
+        assert_eq!(visitor.errors[0].path, "attributes[0].value");
+        assert_eq!(visitor.errors[0].value.as_deref(), Some("\"{\""));
+        assert_eq!(expr.as_jsx_element().unwrap().opening.attrs.len(), 1);
+    }
 }
@@ -0,0 +1,305 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use crate::pipeline::ElementPass;
+
+/// Namespaces editors store their own data in.
+const EDITOR_NAMESPACES: [&str; 13] = [
+    "http://www.bohemiancoding.com/sketch/ns",
+    "http://www.inkscape.org/namespaces/inkscape",
+    "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd",
+    "http://ns.adobe.com/AdobeIllustrator/10.0/",
+    "http://ns.adobe.com/AdobeSVGViewerExtensions/3.0/",
+    "http://ns.adobe.com/Extensibility/1.0/",
+    "http://ns.adobe.com/Flows/1.0/",
+    "http://ns.adobe.com/GenericCustomNamespace/1.0/",
+    "http://ns.adobe.com/Graphs/1.0/",
+    "http://ns.adobe.com/ImageReplacement/1.0/",
+    "http://ns.adobe.com/SaveForWeb/1.0/",
+    "http://ns.adobe.com/Variables/1.0/",
+    "http://www.serif.com/",
+];
+
+/// Prefixes removed even when their declaration is missing, e.g. because an
+/// earlier tool dropped it.
+const EDITOR_PREFIXES: [&str; 3] = ["sketch", "inkscape", "sodipodi"];
+
+/// Descriptions editors add on their own, such as `Created with Sketch.`.
+const GENERATOR_DESCRIPTIONS: [&str; 2] = ["Created with", "Created using"];
+
+fn true_by_default() -> bool {
+    true
+}
+
+/// Every rule is enabled unless set to `false`.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Options {
+    /// Removes `sketch:*`, `inkscape:*`, `sodipodi:*` and Illustrator
+    /// attributes and elements, along with their `xmlns:*` declarations.
+    #[serde(default = "true_by_default")]
+    pub editor_namespaces: bool,
+    /// Removes `<metadata>` elements.
+    #[serde(default = "true_by_default")]
+    pub metadata: bool,
+    /// Removes `<desc>` elements that are empty or only name the editor.
+    #[serde(default = "true_by_default")]
+    pub generator_descriptions: bool,
+    /// Removes `xmlns:*` declarations no element or attribute refers to.
+    #[serde(default = "true_by_default")]
+    pub unused_namespaces: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            editor_namespaces: true,
+            metadata: true,
+            generator_descriptions: true,
+            unused_namespaces: true,
+        }
+    }
+}
+
+pub struct CleanupVisitor {
+    options: Options,
+    /// Prefixes bound to an editor namespace by the elements entered so far.
+    editor_prefixes: HashSet<String>,
+    /// For each entered element declaring namespaces, the prefixes its
+    /// subtree uses. They are collected on entry, before the children are
+    /// transformed and `xlink:href` becomes `xlinkHref`.
+    used_prefixes: Vec<Option<HashSet<String>>>,
+}
+
+impl CleanupVisitor {
+    pub fn new(options: Options) -> Self {
+        CleanupVisitor {
+            options,
+            editor_prefixes: EDITOR_PREFIXES
+                .iter()
+                .map(|prefix| prefix.to_string())
+                .collect(),
+            used_prefixes: vec![],
+        }
+    }
+
+    fn is_editor_prefix(&self, prefix: &str) -> bool {
+        self.options.editor_namespaces && self.editor_prefixes.contains(prefix)
+    }
+
+    fn is_removed_attr(&self, attr: &JSXAttrOrSpread) -> bool {
+        match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }),
+                ..
+            }) => {
+                self.is_editor_prefix(&ns.sym)
+                    || (&*ns.sym == "xmlns" && self.is_editor_prefix(&name.sym))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_removed_child(&self, child: &JSXElementChild) -> bool {
+        let child_element = match child {
+            JSXElementChild::JSXElement(child_element) => child_element,
+            _ => return false,
+        };
+
+        match &child_element.opening.name {
+            JSXElementName::JSXNamespacedName(JSXNamespacedName { ns, .. }) => {
+                self.is_editor_prefix(&ns.sym)
+            }
+            JSXElementName::Ident(Ident { sym, .. }) => {
+                (self.options.metadata && sym == "metadata")
+                    || (self.options.generator_descriptions
+                        && sym == "desc"
+                        && is_generator_description(child_element))
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects the prefixes used by the names in `jsx_element` and the
+    /// descendants the cleanup keeps.
+    fn collect_prefixes(&self, jsx_element: &JSXElement, used: &mut HashSet<String>) {
+        if let JSXElementName::JSXNamespacedName(JSXNamespacedName { ns, .. }) =
+            &jsx_element.opening.name
+        {
+            used.insert(ns.sym.to_string());
+        }
+
+        for attr in &jsx_element.opening.attrs {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, .. }),
+                ..
+            }) = attr
+            {
+                if &*ns.sym != "xmlns" && !self.is_removed_attr(attr) {
+                    used.insert(ns.sym.to_string());
+                }
+            }
+        }
+
+        for child in &jsx_element.children {
+            if let JSXElementChild::JSXElement(child_element) = child {
+                if !self.is_removed_child(child) {
+                    self.collect_prefixes(child_element, used);
+                }
+            }
+        }
+    }
+}
+
+impl ElementPass for CleanupVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        let mut declares_namespaces = false;
+        for attr in &jsx_element.opening.attrs {
+            if let Some((prefix, uri)) = namespace_declaration(attr) {
+                declares_namespaces = true;
+                if EDITOR_NAMESPACES.contains(&uri) {
+                    self.editor_prefixes.insert(prefix.to_string());
+                }
+            }
+        }
+
+        let used = (self.options.unused_namespaces && declares_namespaces).then(|| {
+            let mut used = HashSet::new();
+            self.collect_prefixes(jsx_element, &mut used);
+            used
+        });
+        self.used_prefixes.push(used);
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element
+            .children
+            .retain(|child| !self.is_removed_child(child));
+        jsx_element
+            .opening
+            .attrs
+            .retain(|attr| !self.is_removed_attr(attr));
+
+        if let Some(used) = self.used_prefixes.pop().flatten() {
+            jsx_element
+                .opening
+                .attrs
+                .retain(|attr| match namespace_declaration(attr) {
+                    Some((prefix, _)) => used.contains(prefix),
+                    None => true,
+                });
+        }
+    }
+}
+
+impl VisitMut for CleanupVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        self.enter_element(jsx_element);
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+/// `xmlns:prefix="uri"` gives `(prefix, uri)`.
+fn namespace_declaration(attr: &JSXAttrOrSpread) -> Option<(&str, &str)> {
+    match attr {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }),
+            value,
+            ..
+        }) if &*ns.sym == "xmlns" => {
+            let uri = match value {
+                Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => &**value,
+                _ => "",
+            };
+            Some((&*name.sym, uri))
+        }
+        _ => None,
+    }
+}
+
+fn is_generator_description(jsx_element: &JSXElement) -> bool {
+    let mut text = String::new();
+    for child in &jsx_element.children {
+        match child {
+            JSXElementChild::JSXText(JSXText { value, .. }) => text.push_str(value),
+            _ => return false,
+        }
+    }
+
+    let text = text.trim();
+    text.is_empty()
+        || GENERATOR_DESCRIPTIONS
+            .iter()
+            .any(|description| text.starts_with(description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pipeline::ElementPipeline, transform_attribute::TransformAttributeVisitor};
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupVisitor::new(Options::default())),
+        remove_editor_data,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:sketch="http://www.bohemiancoding.com/sketch/ns" xmlns:i="http://ns.adobe.com/AdobeIllustrator/10.0/" i:viewOrigin="0 0"><desc>Created with Sketch.</desc><metadata><rdf:RDF /></metadata><sodipodi:namedview pagecolor="#fff" /><g sketch:type="MSPage" inkscape:label="Layer 1"><path d="M0 0h24v24H0z" /></g></svg>;"##,
+        r#"<svg xmlns="http://www.w3.org/2000/svg"><g><path d="M0 0h24v24H0z" /></g></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupVisitor::new(Options::default())),
+        keep_used_namespaces_and_descriptions,
+        r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><desc>A red circle</desc><use xlink:href="#a" /></svg>;"##,
+        r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><desc>A red circle</desc><use xlink:href="#a" /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupVisitor::new(Options {
+            metadata: false,
+            generator_descriptions: false,
+            unused_namespaces: false,
+            ..Default::default()
+        })),
+        toggle_rules,
+        r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:sketch="http://www.bohemiancoding.com/sketch/ns"><desc>Created with Sketch.</desc><metadata /><g sketch:type="MSPage" /></svg>;"#,
+        r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><desc>Created with Sketch.</desc><metadata /><g /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| {
+            let mut pipeline = ElementPipeline::default();
+            pipeline.push("cleanup", Box::new(CleanupVisitor::new(Options::default())));
+            pipeline.push("transform_attribute", Box::new(TransformAttributeVisitor));
+            as_folder(pipeline)
+        },
+        keep_namespaces_used_by_transformed_children,
+        r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:dc="http://purl.org/dc/elements/1.1/"><metadata><dc:title /></metadata><use xlink:href="#a" /></svg>;"##,
+        r##"<svg xmlnsXlink="http://www.w3.org/1999/xlink"><use xlinkHref="#a" /></svg>;"##
+    );
+}
@@ -0,0 +1,243 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::error::ConfigError;
+
+use crate::{
+    path_data::{clamp_precision, format_number},
+    pipeline::ElementPass,
+    transform_attribute::is_numeric,
+};
+
+/// Attributes holding a single length or number.
+const NUMERIC_ATTRIBUTES: [&str; 18] = [
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "fx",
+    "fy",
+    "r",
+    "rx",
+    "ry",
+    "width",
+    "height",
+    "stroke-width",
+    "stroke-miterlimit",
+    "stroke-dashoffset",
+];
+
+/// Attributes holding a list of numbers.
+const NUMBER_LIST_ATTRIBUTES: [&str; 3] = ["viewBox", "points", "stroke-dasharray"];
+
+fn default_precision() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Options {
+    /// Number of decimals numbers are rounded to, at most 15.
+    #[serde(default = "default_precision")]
+    pub precision: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            precision: default_precision(),
+        }
+    }
+}
+
+/// Rounds the numbers of geometry attributes and inline styles, and writes
+/// them without trailing zeros, leading zero nor `px` unit. Values that are not
+/// made of numbers are left as is.
+pub struct CleanupNumericValuesVisitor {
+    options: Options,
+    errors: Vec<ConfigError>,
+}
+
+impl CleanupNumericValuesVisitor {
+    pub fn new(mut options: Options) -> Self {
+        let (precision, err) = clamp_precision(options.precision);
+        options.precision = precision;
+        CleanupNumericValuesVisitor {
+            options,
+            errors: err.into_iter().collect(),
+        }
+    }
+
+    fn cleanup_value(&self, name: &str, value: &str) -> Option<String> {
+        if NUMERIC_ATTRIBUTES.contains(&name) {
+            self.cleanup_length(value)
+        } else if NUMBER_LIST_ATTRIBUTES.contains(&name) {
+            self.cleanup_number_list(value)
+        } else if name == "transform" {
+            self.cleanup_transform(value)
+        } else if name == "style" {
+            Some(self.cleanup_style(value))
+        } else {
+            None
+        }
+    }
+
+    fn cleanup_number(&self, number: &str) -> Option<String> {
+        if !is_numeric(number) {
+            return None;
+        }
+
+        // `is_numeric` also accepts `inf` and `NaN`.
+        let number = number
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())?;
+        Some(format_number(number, self.options.precision))
+    }
+
+    /// `10.000px` gives `10`, `0.50em` gives `.5em`.
+    fn cleanup_length(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%');
+        let unit = &value[number.len()..];
+        let number = self.cleanup_number(number)?;
+        Some(if unit == "px" { number } else { number + unit })
+    }
+
+    fn cleanup_number_list(&self, value: &str) -> Option<String> {
+        value
+            .split(|c: char| c.is_ascii_whitespace() || c == ',')
+            .filter(|number| !number.is_empty())
+            .map(|number| self.cleanup_number(number))
+            .collect::<Option<Vec<_>>>()
+            .map(|numbers| numbers.join(" "))
+    }
+
+    /// `matrix(1.0000, 0, 0, 1, 10.5, 0)` gives `matrix(1 0 0 1 10.5 0)`.
+    fn cleanup_transform(&self, value: &str) -> Option<String> {
+        let mut functions = vec![];
+        let mut rest = value.trim();
+        while !rest.is_empty() {
+            let (name, after_name) = rest.split_once('(')?;
+            let (arguments, after_arguments) = after_name.split_once(')')?;
+            let name = name.trim_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+                return None;
+            }
+            functions.push(format!(
+                "{}({})",
+                name,
+                self.cleanup_number_list(arguments)?
+            ));
+            rest =
+                after_arguments.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        }
+
+        Some(functions.join(" "))
+    }
+
+    /// Only the values of the declarations are changed, so that
+    /// `TransformAttributeVisitor` gives the same property names.
+    fn cleanup_style(&self, style: &str) -> String {
+        style
+            .split(';')
+            .map(|declaration| match declaration.split_once(':') {
+                Some((property, value)) => match self.cleanup_length(value) {
+                    Some(value) => format!("{}:{}", property, value),
+                    None => declaration.to_string(),
+                },
+                None => declaration.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+impl ElementPass for CleanupNumericValuesVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(str))),
+                ..
+            }) = attr
+            {
+                if let Some(value) = self.cleanup_value(sym, &str.value) {
+                    if value != *str.value {
+                        str.value = value.into();
+                        str.raw = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for CleanupNumericValuesVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupNumericValuesVisitor::new(Options::default())),
+        cleanup_numeric_values,
+        r#"<svg width="24.000px" height="100%" viewBox="0, 0, 24.0000, 24.12345"><rect x="0.5" y="-0.25" rx="1.50em" stroke-width="2.0px" transform="translate(10.0, 0.5) matrix(1.00001 0 0 1 0 0)" /><polygon points="0.0,1.5 2.25,3" /></svg>;"#,
+        r#"<svg width="24" height="100%" viewBox="0 0 24 24.123"><rect x=".5" y="-.25" rx="1.5em" stroke-width="2" transform="translate(10 .5) matrix(1 0 0 1 0 0)" /><polygon points="0 1.5 2.25 3" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupNumericValuesVisitor::new(Options { precision: 1 })),
+        keep_other_values,
+        r#"<svg><g style="opacity: 0.75; stroke-width: 1.25px; fill: red" width="auto" transform="rotate(45deg)" fill="0.123" /></svg>;"#,
+        r#"<svg><g style="opacity:.8; stroke-width:1.3; fill: red" width="auto" transform="rotate(45deg)" fill="0.123" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CleanupNumericValuesVisitor::new(Options { precision: 400 })),
+        clamp_large_precision,
+        r#"<svg><rect x="0.5" width="10.000px" style="opacity: 0.75" /></svg>;"#,
+        r#"<svg><rect x=".5" width="10" style="opacity:.75" /></svg>;"#
+    );
+
+    #[test]
+    fn report_too_large_precision() {
+        let errors = CleanupNumericValuesVisitor::new(Options { precision: 400 }).take_errors();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "precision");
+        assert_eq!(errors[0].value.as_deref(), Some("400"));
+    }
+}
@@ -0,0 +1,541 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use crate::{
+    path_data::{bounding_box, parse_path, Bounds},
+    pipeline::{element_name, normalize, ElementPass},
+};
+
+/// Attributes of a group that can be moved to its only child: the inherited
+/// presentation attributes, as in SVGO's `collapseGroups`, along with
+/// `transform` and `opacity`, which apply the same to a single child. Any
+/// other attribute keeps the group, as it may be referenced, styled, or apply
+/// to the group as a whole. Names are compared with `normalize`.
+const MOVABLE_ATTRIBUTES: [&str; 46] = [
+    "cliprule",
+    "color",
+    "colorinterpolation",
+    "colorinterpolationfilters",
+    "colorprofile",
+    "colorrendering",
+    "cursor",
+    "direction",
+    "dominantbaseline",
+    "fill",
+    "fillopacity",
+    "fillrule",
+    "font",
+    "fontfamily",
+    "fontsize",
+    "fontsizeadjust",
+    "fontstretch",
+    "fontstyle",
+    "fontvariant",
+    "fontweight",
+    "glyphorientationhorizontal",
+    "glyphorientationvertical",
+    "imagerendering",
+    "letterspacing",
+    "marker",
+    "markerend",
+    "markermid",
+    "markerstart",
+    "opacity",
+    "paintorder",
+    "pointerevents",
+    "shaperendering",
+    "stroke",
+    "strokedasharray",
+    "strokedashoffset",
+    "strokelinecap",
+    "strokelinejoin",
+    "strokemiterlimit",
+    "strokeopacity",
+    "strokewidth",
+    "textanchor",
+    "textrendering",
+    "transform",
+    "visibility",
+    "wordspacing",
+    "writingmode",
+];
+
+/// Attributes that keep a path from being merged with its siblings.
+const PATH_ATTRIBUTES: [&str; 11] = [
+    "id",
+    "class",
+    "classname",
+    "style",
+    "clippath",
+    "mask",
+    "filter",
+    "marker",
+    "markerstart",
+    "markermid",
+    "markerend",
+];
+
+const ANIMATION_ELEMENTS: [&str; 5] = [
+    "animate",
+    "animatecolor",
+    "animatemotion",
+    "animatetransform",
+    "set",
+];
+
+/// Attribute `(name, value)` pairs, with normalized names.
+type Attributes = Vec<(String, String)>;
+
+fn true_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Options {
+    /// Merges sibling paths with the same attributes.
+    #[serde(default = "true_by_default")]
+    pub merge_paths: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { merge_paths: true }
+    }
+}
+
+/// Replaces `<g>` elements without attributes by their children, moves the
+/// `MOVABLE_ATTRIBUTES` of a group with a single child to that child, and
+/// merges adjacent paths with the same attributes into one.
+///
+/// Children are changed once every pass went through them, so element and
+/// attribute names are compared whether they were transformed or not: `g` and
+/// `G`, `clip-path` and `clipPath`.
+///
+/// Paths are only merged when nothing can stroke them, their boxes do not
+/// overlap and the document has no `<style>` element, so that neither the
+/// fill rule nor the painting order changes the rendering.
+///
+/// In a pipeline, the tree is collapsed once the root element went through
+/// the other passes, as `inline_styles` may remove the `<style>` elements
+/// only then.
+pub struct CollapseGroupsVisitor {
+    options: Options,
+    /// Whether a stroke is inherited by the children of each entered element.
+    stroked: Vec<bool>,
+    /// Found when entering the root element.
+    has_style_sheet: bool,
+    /// Number of elements entered by the pipeline and not exited yet.
+    depth: usize,
+}
+
+impl CollapseGroupsVisitor {
+    pub fn new(options: Options) -> Self {
+        CollapseGroupsVisitor {
+            options,
+            stroked: vec![],
+            has_style_sheet: false,
+            depth: 0,
+        }
+    }
+
+    fn collapse_groups(&self, jsx_element: &mut JSXElement) {
+        if element_name(jsx_element).as_deref() == Some("switch") {
+            return;
+        }
+
+        let children = std::mem::take(&mut jsx_element.children);
+        for child in children {
+            match child {
+                JSXElementChild::JSXElement(group)
+                    if element_name(&group).as_deref() == Some("g") =>
+                {
+                    if group.opening.attrs.is_empty() {
+                        jsx_element.children.extend(group.children);
+                    } else {
+                        let child = match collapse_group(group) {
+                            Ok(child) => child,
+                            Err(group) => group,
+                        };
+                        jsx_element
+                            .children
+                            .push(JSXElementChild::JSXElement(child));
+                    }
+                }
+                child => jsx_element.children.push(child),
+            }
+        }
+    }
+
+    fn merge_paths(&self, jsx_element: &mut JSXElement, stroked: bool) {
+        let children = std::mem::take(&mut jsx_element.children);
+        // The last path kept, with the attributes compared and the box of the
+        // paths merged into it.
+        let mut last: Option<(usize, Attributes, Bounds)> = None;
+        for child in children {
+            if let JSXElementChild::JSXElement(path) = &child {
+                if let Some((attributes, bounds)) = mergeable_path(path, stroked) {
+                    if let Some((index, last_attributes, last_bounds)) = &mut last {
+                        if *last_attributes == attributes && !overlap(*last_bounds, bounds) {
+                            if let JSXElementChild::JSXElement(last_path) =
+                                &mut jsx_element.children[*index]
+                            {
+                                append_path_data(last_path, path);
+                                *last_bounds = union(*last_bounds, bounds);
+                                continue;
+                            }
+                        }
+                    }
+
+                    last = Some((jsx_element.children.len(), attributes, bounds));
+                    jsx_element.children.push(child);
+                    continue;
+                }
+            }
+
+            if !is_whitespace(&child) {
+                last = None;
+            }
+            jsx_element.children.push(child);
+        }
+    }
+}
+
+impl ElementPass for CollapseGroupsVisitor {
+    fn enter_element(&mut self, _jsx_element: &mut JSXElement) {
+        self.depth += 1;
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.visit_mut_jsx_element(jsx_element);
+        }
+    }
+}
+
+impl VisitMut for CollapseGroupsVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        let inherited = match self.stroked.last() {
+            Some(stroked) => *stroked,
+            None => {
+                self.has_style_sheet = has_style_sheet(jsx_element);
+                false
+            }
+        };
+        self.stroked.push(is_stroked(jsx_element, inherited));
+        jsx_element.visit_mut_children_with(self);
+        let stroked = self.stroked.pop().unwrap_or(true);
+
+        self.collapse_groups(jsx_element);
+        if self.options.merge_paths && !self.has_style_sheet {
+            self.merge_paths(jsx_element, stroked);
+        }
+    }
+}
+
+/// Moves the attributes of `group` to its only child element, or gives the
+/// group back when that would change the rendering.
+fn collapse_group(mut group: Box<JSXElement>) -> Result<Box<JSXElement>, Box<JSXElement>> {
+    let group_attributes = match string_attributes(&group) {
+        Some(attributes) => attributes,
+        None => return Err(group),
+    };
+    if group_attributes
+        .iter()
+        .any(|(name, value)| !MOVABLE_ATTRIBUTES.contains(&name.as_str()) || value.contains("url("))
+    {
+        return Err(group);
+    }
+
+    let mut elements = group.children.iter().filter(|child| !is_whitespace(child));
+    let child = match (elements.next(), elements.next()) {
+        (Some(JSXElementChild::JSXElement(child)), None) => child,
+        _ => return Err(group),
+    };
+    let child_attributes = match string_attributes(child) {
+        Some(attributes) => attributes,
+        None => return Err(group),
+    };
+    let child_has = |name: &str| child_attributes.iter().any(|(other, _)| other == name);
+    // A referenced child would take the group attributes along when used.
+    if child_has("id")
+        || element_name(child).is_none_or(|name| ANIMATION_ELEMENTS.contains(&name.as_str()))
+    {
+        return Err(group);
+    }
+    for (name, _) in &group_attributes {
+        let conflicts = if name == "transform" {
+            // Clip paths and masks are positioned in the child's own space.
+            child_has("clippath") || child_has("mask")
+        } else {
+            child_has(name)
+        };
+        if conflicts {
+            return Err(group);
+        }
+    }
+
+    let mut child = match group.children.drain(..).find(|child| !is_whitespace(child)) {
+        Some(JSXElementChild::JSXElement(child)) => child,
+        _ => unreachable!(),
+    };
+    let mut attrs = vec![];
+    for attr in group.opening.attrs {
+        match &attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(group_transform))),
+                ..
+            }) if sym == "transform" => {
+                if let Some(Lit::Str(child_transform)) =
+                    attribute_value_mut(&mut child, "transform")
+                {
+                    // The group transform applies first.
+                    child_transform.value =
+                        format!("{} {}", group_transform.value, child_transform.value).into();
+                    child_transform.raw = None;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        attrs.push(attr);
+    }
+    attrs.append(&mut child.opening.attrs);
+    child.opening.attrs = attrs;
+
+    Ok(child)
+}
+
+/// The attributes to compare and the box of a path that can be merged, which
+/// has no children and only string attributes, none of `PATH_ATTRIBUTES` nor
+/// references to paint servers.
+fn mergeable_path(path: &JSXElement, stroked: bool) -> Option<(Attributes, Bounds)> {
+    if element_name(path).as_deref() != Some("path")
+        || path.children.iter().any(|child| !is_whitespace(child))
+    {
+        return None;
+    }
+
+    let mut attributes = string_attributes(path)?;
+    if attributes
+        .iter()
+        .any(|(name, value)| PATH_ATTRIBUTES.contains(&name.as_str()) || value.contains("url("))
+    {
+        return None;
+    }
+
+    let stroke = attributes.iter().find(|(name, _)| name == "stroke");
+    if stroke.map_or(stroked, |(_, value)| value != "none") {
+        return None;
+    }
+
+    let index = attributes.iter().position(|(name, _)| name == "d")?;
+    let (_, d) = attributes.remove(index);
+    // Data starting with a relative moveto would move along with the end of
+    // the previous path.
+    if !d.trim_start().starts_with('M') {
+        return None;
+    }
+    let bounds = bounding_box(&parse_path(&d).ok()?)?;
+
+    attributes.sort();
+    Some((attributes, bounds))
+}
+
+fn append_path_data(path: &mut JSXElement, other: &JSXElement) {
+    let other_d = other.opening.attrs.iter().find_map(|attr| match attr {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+            ..
+        }) if sym == "d" => Some(value.clone()),
+        _ => None,
+    });
+
+    if let (Some(Lit::Str(d)), Some(other_d)) = (attribute_value_mut(path, "d"), other_d) {
+        d.value = format!("{}{}", d.value.trim_end(), other_d.trim_start()).into();
+        d.raw = None;
+    }
+}
+
+/// The `(name, value)` pairs of the attributes, with normalized names, when
+/// they are all written as strings, without spreads nor expressions.
+fn string_attributes(jsx_element: &JSXElement) -> Option<Attributes> {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name,
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+                ..
+            }) => {
+                let name = match name {
+                    JSXAttrName::Ident(Ident { sym, .. }) => normalize(sym),
+                    JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+                        normalize(&format!("{}{}", ns.sym, name.sym))
+                    }
+                };
+                Some((name, value.to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn attribute_value_mut<'a>(jsx_element: &'a mut JSXElement, name: &str) -> Option<&'a mut Lit> {
+    jsx_element
+        .opening
+        .attrs
+        .iter_mut()
+        .find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::Lit(lit)),
+                ..
+            }) if sym == name => Some(lit),
+            _ => None,
+        })
+}
+
+/// Whether the children of `jsx_element` may be stroked. Inline styles may
+/// set a stroke too.
+fn is_stroked(jsx_element: &JSXElement, inherited: bool) -> bool {
+    let mut stroked = inherited;
+    for attr in &jsx_element.opening.attrs {
+        match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value,
+                ..
+            }) if sym == "stroke" => {
+                stroked = !matches!(
+                    value,
+                    Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) if &**value == "none"
+                );
+            }
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                ..
+            }) if sym == "style" => stroked = true,
+            JSXAttrOrSpread::SpreadElement(_) => stroked = true,
+            _ => {}
+        }
+    }
+    stroked
+}
+
+fn has_style_sheet(jsx_element: &JSXElement) -> bool {
+    element_name(jsx_element).as_deref() == Some("style")
+        || jsx_element.children.iter().any(|child| match child {
+            JSXElementChild::JSXElement(child) => has_style_sheet(child),
+            _ => false,
+        })
+}
+
+fn is_whitespace(child: &JSXElementChild) -> bool {
+    matches!(child, JSXElementChild::JSXText(JSXText { value, .. }) if value.trim().is_empty())
+}
+
+fn overlap((min, max): Bounds, (other_min, other_max): Bounds) -> bool {
+    min.0 <= other_max.0 && other_min.0 <= max.0 && min.1 <= other_max.1 && other_min.1 <= max.1
+}
+
+fn union((min, max): Bounds, (other_min, other_max): Bounds) -> Bounds {
+    (
+        (min.0.min(other_min.0), min.1.min(other_min.1)),
+        (max.0.max(other_max.0), max.1.max(other_max.1)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pipeline::ElementPipeline, transform_attribute::TransformAttributeVisitor};
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CollapseGroupsVisitor::new(Options::default())),
+        collapse_groups,
+        r#"<svg><g><g fill="red" transform="translate(1 1)"><g transform="scale(2)"><path transform="rotate(45)" d="M0 0h1v1z" /></g></g></g><g><rect /><circle /></g></svg>;"#,
+        r#"<svg><path fill="red" transform="translate(1 1) scale(2) rotate(45)" d="M0 0h1v1z" /><rect /><circle /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CollapseGroupsVisitor::new(Options::default())),
+        keep_groups_changing_rendering,
+        r##"<svg><g id="a"><path d="M0 0h1" /></g><g clip-path="url(#c)"><path d="M0 0h1" /></g><g fill="red"><path fill="blue" d="M0 0h1" /></g><g transform="scale(2)"><path mask="url(#m)" d="M0 0h1" /></g><g fill="red"><use href="#a" /><path d="M0 0h1" /></g><g opacity=".5"><path id="b" d="M0 0h1" /></g></svg>;"##,
+        r##"<svg><g id="a"><path d="M0 0h1" /></g><g clip-path="url(#c)"><path d="M0 0h1" /></g><g fill="red"><path fill="blue" d="M0 0h1" /></g><g transform="scale(2)"><path mask="url(#m)" d="M0 0h1" /></g><g fill="red"><use href="#a" /><path d="M0 0h1" /></g><g opacity=".5"><path id="b" d="M0 0h1" /></g></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CollapseGroupsVisitor::new(Options::default())),
+        keep_groups_with_attributes_not_inherited,
+        r#"<svg><g width="3" x="4"><rect height="2" /></g><g data-name="Layer" fill="red"><path d="M0 0h1" /></g><g font-size="2" stroke-linecap="round"><text>A</text></g></svg>;"#,
+        r#"<svg><g width="3" x="4"><rect height="2" /></g><g data-name="Layer" fill="red"><path d="M0 0h1" /></g><text font-size="2" stroke-linecap="round">A</text></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CollapseGroupsVisitor::new(Options::default())),
+        merge_paths,
+        r##"<svg><path fill="red" d="M0 0h1v1z" /><path d="M5 5h1v1z" fill="red" /><path fill="red" d="M10 10h1v1z" /><path fill="red" d="M10.5 10.5h1v1z" /><path fill="blue" d="M20 20h1v1z" /><path fill="blue" d="m30 30h1v1z" /><g stroke="#000"><path d="M0 0h1" /><path d="M5 5h1" /></g></svg>;"##,
+        r##"<svg><path fill="red" d="M0 0h1v1zM5 5h1v1zM10 10h1v1z" /><path fill="red" d="M10.5 10.5h1v1z" /><path fill="blue" d="M20 20h1v1z" /><path fill="blue" d="m30 30h1v1z" /><g stroke="#000"><path d="M0 0h1" /><path d="M5 5h1" /></g></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CollapseGroupsVisitor::new(Options::default())),
+        keep_paths_with_style_sheet,
+        r#"<svg><style>{"path { stroke: red }"}</style><path d="M0 0h1" /><path d="M5 5h1" /></svg>;"#,
+        r#"<svg><style>{"path { stroke: red }"}</style><path d="M0 0h1" /><path d="M5 5h1" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| {
+            let mut pipeline = ElementPipeline::default();
+            pipeline.push(
+                "collapse_groups",
+                Box::new(CollapseGroupsVisitor::new(Options::default())),
+            );
+            pipeline.push("transform_attribute", Box::new(TransformAttributeVisitor));
+            as_folder(pipeline)
+        },
+        compare_transformed_names,
+        r#"<svg><g fill-rule="evenodd"><path fill-rule="nonzero" d="M0 0h1" /></g><g stroke-width="2"><path d="M0 0h1" /></g></svg>;"#,
+        r#"<svg><g fillRule="evenodd"><path fillRule="nonzero" d="M0 0h1" /></g><path strokeWidth="2" d="M0 0h1" /></svg>;"#
+    );
+}
@@ -0,0 +1,342 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::error::ConfigError;
+
+use crate::{
+    path_data::{clamp_precision, minify_path, Point, Segment},
+    pipeline::{element_name, ElementPass},
+};
+
+fn default_precision() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Options {
+    /// Number of decimals kept in the coordinates of the path data, at most 15.
+    #[serde(default = "default_precision")]
+    pub precision: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            precision: default_precision(),
+        }
+    }
+}
+
+/// Converts `<rect>`, `<circle>`, `<ellipse>`, `<line>`, `<polyline>` and
+/// `<polygon>` elements to `<path>` elements drawing the same shape, so that
+/// the passes working on paths, and the `react-native-svg` imports, only see
+/// one kind of element.
+///
+/// Shapes whose geometry is given by expressions, in units other than `px`
+/// or through spread props are left as is, as are shapes that do not render.
+pub struct ConvertShapesVisitor {
+    options: Options,
+    errors: Vec<ConfigError>,
+}
+
+impl ConvertShapesVisitor {
+    pub fn new(mut options: Options) -> Self {
+        let (precision, err) = clamp_precision(options.precision);
+        options.precision = precision;
+        ConvertShapesVisitor {
+            options,
+            errors: err.into_iter().collect(),
+        }
+    }
+}
+
+impl ElementPass for ConvertShapesVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        let attributes: &[&str] = match element_name(jsx_element).as_deref() {
+            Some("rect") => &["x", "y", "width", "height", "rx", "ry"],
+            Some("circle") => &["cx", "cy", "r"],
+            Some("ellipse") => &["cx", "cy", "rx", "ry"],
+            Some("line") => &["x1", "y1", "x2", "y2"],
+            Some("polyline" | "polygon") => &["points"],
+            _ => return,
+        };
+        if jsx_element
+            .opening
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)))
+        {
+            return;
+        }
+
+        let segments = match shape_segments(jsx_element) {
+            Some(segments) => segments,
+            None => return,
+        };
+
+        jsx_element.opening.attrs.retain(|attr| {
+            !matches!(
+                attr,
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(Ident { sym, .. }),
+                    ..
+                }) if attributes.contains(&&**sym)
+            )
+        });
+        jsx_element
+            .opening
+            .attrs
+            .push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span: DUMMY_SP,
+                name: JSXAttrName::Ident(Ident::new("d".into(), DUMMY_SP)),
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: minify_path(&segments, self.options.precision).into(),
+                    raw: None,
+                }))),
+            }));
+
+        // `Rect` becomes `Path` when `native` already ran.
+        let is_capitalized = matches!(
+            &jsx_element.opening.name,
+            JSXElementName::Ident(Ident { sym, .. }) if sym.starts_with(char::is_uppercase)
+        );
+        let name = JSXElementName::Ident(Ident::new(
+            if is_capitalized { "Path" } else { "path" }.into(),
+            DUMMY_SP,
+        ));
+        jsx_element.opening.name = name.clone();
+        if let Some(closing) = jsx_element.closing.as_mut() {
+            closing.name = name;
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for ConvertShapesVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+/// The path segments drawing the shape of `jsx_element`, or `None` when its
+/// geometry cannot be read or it does not render.
+fn shape_segments(jsx_element: &JSXElement) -> Option<Vec<Segment>> {
+    let number = |name: &str| -> Option<Option<f64>> {
+        let attr = jsx_element
+            .opening
+            .attrs
+            .iter()
+            .find_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(Ident { sym, .. }),
+                    value,
+                    ..
+                }) if &**sym == name => Some(value),
+                _ => None,
+            });
+        match attr {
+            None => Some(None),
+            Some(Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. })))) => {
+                parse_length(value).map(Some)
+            }
+            Some(Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            }))) => match &**expr {
+                Expr::Lit(Lit::Num(Number { value, .. })) => Some(Some(*value)),
+                _ => None,
+            },
+            Some(_) => None,
+        }
+    };
+    let length = |name: &str| number(name).map(|value| value.unwrap_or(0.0));
+
+    match element_name(jsx_element).as_deref()? {
+        "rect" => {
+            let (x, y) = (length("x")?, length("y")?);
+            let (width, height) = (length("width")?, length("height")?);
+            if width <= 0.0 || height <= 0.0 {
+                return None;
+            }
+            // A missing radius takes the value of the other one.
+            let (rx, ry) = match (number("rx")?, number("ry")?) {
+                (None, None) => (0.0, 0.0),
+                (Some(rx), None) => (rx, rx),
+                (None, Some(ry)) => (ry, ry),
+                (Some(rx), Some(ry)) => (rx, ry),
+            };
+            Some(rect(x, y, width, height, rx, ry))
+        }
+        "circle" | "ellipse" => {
+            let radii = if element_name(jsx_element).as_deref() == Some("circle") {
+                let r = length("r")?;
+                (r, r)
+            } else {
+                (length("rx")?, length("ry")?)
+            };
+            if radii.0 <= 0.0 || radii.1 <= 0.0 {
+                return None;
+            }
+            Some(ellipse((length("cx")?, length("cy")?), radii))
+        }
+        "line" => Some(vec![
+            Segment::MoveTo((length("x1")?, length("y1")?)),
+            Segment::LineTo((length("x2")?, length("y2")?)),
+        ]),
+        name @ ("polyline" | "polygon") => {
+            let points = match jsx_element
+                .opening
+                .attrs
+                .iter()
+                .find_map(|attr| match attr {
+                    JSXAttrOrSpread::JSXAttr(JSXAttr {
+                        name: JSXAttrName::Ident(Ident { sym, .. }),
+                        value,
+                        ..
+                    }) if &**sym == "points" => Some(value),
+                    _ => None,
+                }) {
+                Some(Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. })))) => parse_points(value)?,
+                _ => return None,
+            };
+            if points.len() < 2 {
+                return None;
+            }
+
+            let mut segments = vec![Segment::MoveTo(points[0])];
+            segments.extend(points[1..].iter().map(|point| Segment::LineTo(*point)));
+            if name == "polygon" {
+                segments.push(Segment::Close);
+            }
+            Some(segments)
+        }
+        _ => None,
+    }
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64, rx: f64, ry: f64) -> Vec<Segment> {
+    let (right, bottom) = (x + width, y + height);
+    let (rx, ry) = (rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0));
+    if rx == 0.0 || ry == 0.0 {
+        return vec![
+            Segment::MoveTo((x, y)),
+            Segment::LineTo((right, y)),
+            Segment::LineTo((right, bottom)),
+            Segment::LineTo((x, bottom)),
+            Segment::Close,
+        ];
+    }
+
+    let corner = |to: Point| Segment::Arc {
+        radii: (rx, ry),
+        rotation: 0.0,
+        large_arc: false,
+        sweep: true,
+        to,
+    };
+    vec![
+        Segment::MoveTo((x + rx, y)),
+        Segment::LineTo((right - rx, y)),
+        corner((right, y + ry)),
+        Segment::LineTo((right, bottom - ry)),
+        corner((right - rx, bottom)),
+        Segment::LineTo((x + rx, bottom)),
+        corner((x, bottom - ry)),
+        Segment::LineTo((x, y + ry)),
+        corner((x + rx, y)),
+        Segment::Close,
+    ]
+}
+
+/// Two half arcs, as a single arc cannot end where it starts.
+fn ellipse((cx, cy): Point, radii: Point) -> Vec<Segment> {
+    let half = |to: Point| Segment::Arc {
+        radii,
+        rotation: 0.0,
+        large_arc: true,
+        sweep: false,
+        to,
+    };
+    vec![
+        Segment::MoveTo((cx - radii.0, cy)),
+        half((cx + radii.0, cy)),
+        half((cx - radii.0, cy)),
+        Segment::Close,
+    ]
+}
+
+/// A number, possibly in `px`.
+fn parse_length(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .trim_end_matches("px")
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// The points of a `points` attribute. An odd number of coordinates is an
+/// error, where renderers draw the points up to it.
+fn parse_points(value: &str) -> Option<Vec<Point>> {
+    let numbers = value
+        .split(|c: char| c.is_ascii_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .map(parse_length)
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        numbers
+            .chunks_exact(2)
+            .map(|point| (point[0], point[1]))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(ConvertShapesVisitor::new(Options::default())),
+        convert_shapes,
+        r##"<svg><rect x="1" y="2" width="10px" height="5" fill="red" /><rect width="10" height="6" rx="2" /><circle cx="12" cy="12" r="10"></circle><ellipse cx="5" cy="5" rx="4" ry="2" /><line x1="0" y1="0" x2="10" y2="10" stroke="#000" /><polyline points="0,0 10,0 10,10 5" /><polygon points="0 0, 10 0, 5 10" /></svg>;"##,
+        r##"<svg><path fill="red" d="M1 2h10v5H1z" /><path d="M2 0h6a2 2 0 0 1 2 2v2a2 2 0 0 1-2 2H2a2 2 0 0 1-2-2V2a2 2 0 0 1 2-2z" /><path d="M2 12a10 10 0 1 0 20 0 10 10 0 1 0-20 0z"></path><path d="M1 5a4 2 0 1 0 8 0 4 2 0 1 0-8 0z" /><path stroke="#000" d="M0 0l10 10" /><path d="M0 0h10v10" /><path d="M0 0h10L5 10z" /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(ConvertShapesVisitor::new(Options::default())),
+        keep_shapes_that_cannot_be_converted,
+        r#"<svg><rect width="100%" height="10" /><rect width={size} height="10" /><circle r="0" /><circle r="2" {...props} /><polyline points="5 5" /><Rect width={10} height={10} /></svg>;"#,
+        r#"<svg><rect width="100%" height="10" /><rect width={size} height="10" /><circle r="0" /><circle r="2" {...props} /><polyline points="5 5" /><Path d="M0 0h10v10H0z" /></svg>;"#
+    );
+
+    #[test]
+    fn report_too_large_precision() {
+        let errors = ConvertShapesVisitor::new(Options { precision: 400 }).take_errors();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "precision");
+        assert_eq!(errors[0].value.as_deref(), Some("400"));
+    }
+}
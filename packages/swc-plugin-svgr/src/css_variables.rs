@@ -0,0 +1,416 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::color_props::{is_color, is_color_property, normalize_color};
+
+use crate::{
+    pipeline::{normalize, ElementPass},
+    references::attribute_name,
+};
+
+/// Name of the static property of the component listing its variables.
+const PROPERTY: &str = "cssVariables";
+
+fn default_prefix() -> String {
+    "{componentName}".to_string()
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Options {
+    /// Starts the variable names, `--{prefix}-color-1`; `{componentName}` is
+    /// replaced with the name of the component in kebab case.
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            prefix: default_prefix(),
+        }
+    }
+}
+
+/// Rewrites the `fill`, `stroke`, `stop-color`, ... colors of every element,
+/// in attributes and inline styles, to CSS variables falling back to them, so
+/// that themes can recolor icons without rendering them again: the colors of
+/// `SvgStar` become `var(--svg-star-color-1, #063855)`, numbered in document
+/// order.
+///
+/// The variables and their default colors are listed in the `cssVariables`
+/// static property of the exported component, so that it is on the `memo` or
+/// `forwardRef` wrapper when there is one.
+pub struct CssVariablesVisitor {
+    component_name: String,
+    prefix: String,
+    /// Normalized colors with their first spelling, in document order.
+    /// Collected when entering the root element.
+    colors: Option<Vec<(String, String)>>,
+}
+
+impl CssVariablesVisitor {
+    pub fn new(options: Options, component_name: &str) -> Self {
+        CssVariablesVisitor {
+            component_name: component_name.to_string(),
+            prefix: options
+                .prefix
+                .replace("{componentName}", &kebab_case(component_name)),
+            colors: None,
+        }
+    }
+
+    fn variable(&self, index: usize) -> String {
+        format!("--{}-color-{}", self.prefix, index + 1)
+    }
+
+    /// `var(--name, color)` for `color`, when it is a color.
+    fn replace(&self, color: &str) -> Option<String> {
+        if !is_color(color) {
+            return None;
+        }
+        let normalized = normalize_color(color);
+        let index = self
+            .colors
+            .as_ref()?
+            .iter()
+            .position(|(other, _)| *other == normalized)?;
+        Some(format!("var({}, {})", self.variable(index), color.trim()))
+    }
+
+    fn replace_style(&self, css: &str) -> Option<String> {
+        let mut replaced = false;
+        let declarations = css
+            .split(';')
+            .map(|declaration| {
+                match declaration.split_once(':').and_then(|(property, value)| {
+                    if !is_color_property(&normalize(property)) {
+                        return None;
+                    }
+                    let space = &value[..value.len() - value.trim_start().len()];
+                    self.replace(value)
+                        .map(|value| format!("{}:{}{}", property, space, value))
+                }) {
+                    Some(declaration) => {
+                        replaced = true;
+                        declaration
+                    }
+                    None => declaration.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        replaced.then(|| declarations.join(";"))
+    }
+
+    /// `binding.cssVariables = { "--svg-star-color-1": "#063855" };`
+    fn property_statement(&self, binding: &str, colors: &[(String, String)]) -> ModuleItem {
+        let string = |value: String| {
+            Box::new(Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: value.into(),
+                raw: None,
+            })))
+        };
+        let props = colors
+            .iter()
+            .enumerate()
+            .map(|(index, (_, color))| {
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                    key: PropName::Str(Str {
+                        span: DUMMY_SP,
+                        value: self.variable(index).into(),
+                        raw: None,
+                    }),
+                    value: string(color.clone()),
+                })))
+            })
+            .collect();
+
+        ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Assign(AssignExpr {
+                span: DUMMY_SP,
+                op: AssignOp::Assign,
+                left: PatOrExpr::Expr(Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(Ident::new(binding.into(), DUMMY_SP))),
+                    prop: MemberProp::Ident(Ident::new(PROPERTY.into(), DUMMY_SP)),
+                }))),
+                right: Box::new(Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props,
+                })),
+            })),
+        }))
+    }
+}
+
+impl ElementPass for CssVariablesVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        if self.colors.is_none() {
+            let mut colors = vec![];
+            collect_colors(jsx_element, &mut colors);
+            self.colors = Some(colors);
+        }
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name, value, .. }) = attr else {
+                continue;
+            };
+            let name = attribute_name(name);
+
+            match value {
+                Some(JSXAttrValue::Lit(Lit::Str(str))) => {
+                    let replaced = if is_color_property(&name) {
+                        self.replace(&str.value)
+                    } else if name == "style" {
+                        self.replace_style(&str.value)
+                    } else {
+                        None
+                    };
+                    if let Some(replaced) = replaced {
+                        str.value = replaced.into();
+                        str.raw = None;
+                    }
+                }
+                // Style objects, once `transform_attribute` ran.
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) if name == "style" => {
+                    let Expr::Object(ObjectLit { props, .. }) = &mut **expr else {
+                        continue;
+                    };
+                    for prop in props.iter_mut() {
+                        let PropOrSpread::Prop(prop) = prop else {
+                            continue;
+                        };
+                        let Prop::KeyValue(KeyValueProp { key, value }) = &mut **prop else {
+                            continue;
+                        };
+                        let key = match key {
+                            PropName::Ident(Ident { sym, .. }) => normalize(sym),
+                            PropName::Str(Str { value, .. }) => normalize(value),
+                            _ => continue,
+                        };
+                        if let Expr::Lit(Lit::Str(str)) = &mut **value {
+                            if let Some(replaced) = is_color_property(&key)
+                                .then(|| self.replace(&str.value))
+                                .flatten()
+                            {
+                                str.value = replaced.into();
+                                str.raw = None;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn finish(&mut self, program: &mut Program) {
+        let colors = match &self.colors {
+            Some(colors) if !colors.is_empty() => colors,
+            _ => return,
+        };
+        let Program::Module(module) = program else {
+            return;
+        };
+
+        // On the exported binding, which `transform_svg_component` declares
+        // last: the component, or the `memo` or `forwardRef` call wrapping it.
+        let mut binding = self.component_name.clone();
+        let mut declaration = None;
+        for (index, item) in module.body.iter().enumerate() {
+            let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = item else {
+                continue;
+            };
+            for decl in &var_decl.decls {
+                let Pat::Ident(BindingIdent { id, .. }) = &decl.name else {
+                    continue;
+                };
+                if *id.sym == *binding {
+                    declaration = Some(index);
+                } else if declaration.is_some() && wraps(decl, &binding) {
+                    binding = id.sym.to_string();
+                    declaration = Some(index);
+                }
+            }
+        }
+        if let Some(index) = declaration {
+            let statement = self.property_statement(&binding, colors);
+            module.body.insert(index + 1, statement);
+        }
+    }
+}
+
+/// Whether `decl` initializes its binding with a call on `name`, like
+/// `const Memo = memo(SvgStar)`.
+fn wraps(decl: &VarDeclarator, name: &str) -> bool {
+    let Some(Expr::Call(CallExpr { args, .. })) = decl.init.as_deref() else {
+        return false;
+    };
+    matches!(
+        args.first(),
+        Some(ExprOrSpread { expr, spread: None })
+            if matches!(&**expr, Expr::Ident(Ident { sym, .. }) if **sym == *name)
+    )
+}
+
+impl VisitMut for CssVariablesVisitor {
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        program.visit_mut_children_with(self);
+        self.finish(program);
+    }
+
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        self.enter_element(jsx_element);
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+fn collect_colors(jsx_element: &JSXElement, colors: &mut Vec<(String, String)>) {
+    let mut add = |color: &str| {
+        let normalized = normalize_color(color);
+        if is_color(color) && !colors.iter().any(|(other, _)| *other == normalized) {
+            colors.push((normalized, color.trim().to_string()));
+        }
+    };
+
+    for attr in &jsx_element.opening.attrs {
+        let JSXAttrOrSpread::JSXAttr(JSXAttr { name, value, .. }) = attr else {
+            continue;
+        };
+        let name = attribute_name(name);
+        match value {
+            Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => {
+                if is_color_property(&name) {
+                    add(value);
+                } else if name == "style" {
+                    for declaration in value.split(';') {
+                        if let Some((property, value)) = declaration.split_once(':') {
+                            if is_color_property(&normalize(property)) {
+                                add(value);
+                            }
+                        }
+                    }
+                }
+            }
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) if name == "style" => {
+                if let Expr::Object(ObjectLit { props, .. }) = &**expr {
+                    for prop in props {
+                        if let PropOrSpread::Prop(prop) = prop {
+                            if let Prop::KeyValue(KeyValueProp { key, value }) = &**prop {
+                                let key = match key {
+                                    PropName::Ident(Ident { sym, .. }) => normalize(sym),
+                                    PropName::Str(Str { value, .. }) => normalize(value),
+                                    _ => continue,
+                                };
+                                if let Expr::Lit(Lit::Str(Str { value, .. })) = &**value {
+                                    if is_color_property(&key) {
+                                        add(value);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for child in &jsx_element.children {
+        if let JSXElementChild::JSXElement(child) = child {
+            collect_colors(child, colors);
+        }
+    }
+}
+
+/// `SvgArrowLeft` gives `svg-arrow-left`. Runs of capitals make one word,
+/// the last of them starting the next word: `SVGIcon` gives `svg-icon`.
+fn kebab_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut kebab = String::with_capacity(name.len() + 4);
+    for (index, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let after_lowercase = index > 0 && !chars[index - 1].is_ascii_uppercase();
+            let before_lowercase = index > 0
+                && chars[index - 1].is_ascii_uppercase()
+                && chars.get(index + 1).is_some_and(char::is_ascii_lowercase);
+            if after_lowercase || before_lowercase {
+                kebab.push('-');
+            }
+            kebab.push(c.to_ascii_lowercase());
+        } else {
+            kebab.push(*c);
+        }
+    }
+    kebab
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CssVariablesVisitor::new(Options::default(), "SvgStar")),
+        css_variables,
+        r##"const SvgStar = (props)=><svg fill="none" {...props}><path fill="#063855" stroke="#FFF" style="stop-color: #063855; opacity: 0.5" /><circle fill={color} stroke="url(#a)" style={{ fill: "#fff" }} /></svg>; export default SvgStar;"##,
+        r##"const SvgStar = (props)=><svg fill="none" {...props}><path fill="var(--svg-star-color-1, #063855)" stroke="var(--svg-star-color-2, #FFF)" style="stop-color: var(--svg-star-color-1, #063855); opacity: 0.5" /><circle fill={color} stroke="url(#a)" style={{ fill: "var(--svg-star-color-2, #fff)" }} /></svg>; SvgStar.cssVariables = { "--svg-star-color-1": "#063855", "--svg-star-color-2": "#FFF" }; export default SvgStar;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CssVariablesVisitor::new(
+            Options {
+                prefix: "icon".to_string()
+            },
+            "SvgStar"
+        )),
+        css_variables_with_prefix,
+        r##"const SvgStar = ()=><svg><path fill="red" /></svg>;"##,
+        r##"const SvgStar = ()=><svg><path fill="var(--icon-color-1, red)" /></svg>; SvgStar.cssVariables = { "--icon-color-1": "red" };"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CssVariablesVisitor::new(Options::default(), "SvgStar")),
+        css_variables_on_exported_binding,
+        r##"const SvgStar = (props, ref)=><svg ref={ref}><path fill="red" /></svg>; const ForwardRef = forwardRef(SvgStar); const Memo = memo(ForwardRef); export default Memo;"##,
+        r##"const SvgStar = (props, ref)=><svg ref={ref}><path fill="var(--svg-star-color-1, red)" /></svg>; const ForwardRef = forwardRef(SvgStar); const Memo = memo(ForwardRef); Memo.cssVariables = { "--svg-star-color-1": "red" }; export default Memo;"##
+    );
+
+    #[test]
+    fn kebab_case_component_names() {
+        assert_eq!(kebab_case("SvgArrowLeft"), "svg-arrow-left");
+        assert_eq!(kebab_case("SVGIcon"), "svg-icon");
+        assert_eq!(kebab_case("SvgXMLHttp2"), "svg-xml-http2");
+        assert_eq!(kebab_case("SvgAB"), "svg-ab");
+    }
+}
@@ -0,0 +1,172 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use transform_svg_component::color_props::{is_color, normalize_color};
+
+use crate::{
+    pipeline::{normalize, ElementPass},
+    references::attribute_name,
+};
+
+/// Properties holding the colors to replace, compared with `normalize`.
+const COLOR_PROPERTIES: [&str; 3] = ["fill", "stroke", "stopcolor"];
+
+const CURRENT_COLOR: &str = "currentColor";
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct Options {
+    /// Colors left as is, such as the accent color of a two-color icon.
+    /// `#FFF` keeps `#fff` and `#ffffff` too.
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+/// Replaces the `fill`, `stroke` and `stop-color` colors of every element with
+/// `currentColor`, in attributes and inline styles, so that single-color icons
+/// follow the text color.
+///
+/// `none`, `transparent`, `url()` references and values given as expressions
+/// are left as is, as are the colors of `options.keep`.
+pub struct CurrentColorVisitor {
+    keep: Vec<String>,
+}
+
+impl CurrentColorVisitor {
+    pub fn new(options: Options) -> Self {
+        CurrentColorVisitor {
+            keep: options
+                .keep
+                .iter()
+                .map(|color| normalize_color(color))
+                .collect(),
+        }
+    }
+
+    fn is_replaced(&self, color: &str) -> bool {
+        is_color(color) && !self.keep.contains(&normalize_color(color))
+    }
+
+    /// `css` with the colors of its declarations replaced.
+    fn replace_style(&self, css: &str) -> Option<String> {
+        let mut replaced = false;
+        let declarations = css
+            .split(';')
+            .map(|declaration| match declaration.split_once(':') {
+                Some((property, value))
+                    if COLOR_PROPERTIES.contains(&normalize(property).as_str())
+                        && self.is_replaced(value) =>
+                {
+                    replaced = true;
+                    let space = &value[..value.len() - value.trim_start().len()];
+                    format!("{}:{}{}", property, space, CURRENT_COLOR)
+                }
+                _ => declaration.to_string(),
+            })
+            .collect::<Vec<_>>();
+        replaced.then(|| declarations.join(";"))
+    }
+}
+
+impl ElementPass for CurrentColorVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name, value, .. }) = attr else {
+                continue;
+            };
+            let name = attribute_name(name);
+
+            match value {
+                Some(JSXAttrValue::Lit(Lit::Str(str))) => {
+                    let replaced = if COLOR_PROPERTIES.contains(&name.as_str()) {
+                        self.is_replaced(&str.value)
+                            .then(|| CURRENT_COLOR.to_string())
+                    } else if name == "style" {
+                        self.replace_style(&str.value)
+                    } else {
+                        None
+                    };
+                    if let Some(replaced) = replaced {
+                        str.value = replaced.into();
+                        str.raw = None;
+                    }
+                }
+                // Style objects, once `transform_attribute` ran.
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) if name == "style" => {
+                    let Expr::Object(ObjectLit { props, .. }) = &mut **expr else {
+                        continue;
+                    };
+                    for prop in props.iter_mut() {
+                        let PropOrSpread::Prop(prop) = prop else {
+                            continue;
+                        };
+                        let Prop::KeyValue(KeyValueProp { key, value }) = &mut **prop else {
+                            continue;
+                        };
+                        let key = match key {
+                            PropName::Ident(Ident { sym, .. }) => normalize(sym),
+                            PropName::Str(Str { value, .. }) => normalize(value),
+                            _ => continue,
+                        };
+                        if let Expr::Lit(Lit::Str(str)) = &mut **value {
+                            if COLOR_PROPERTIES.contains(&key.as_str())
+                                && self.is_replaced(&str.value)
+                            {
+                                str.value = CURRENT_COLOR.into();
+                                str.raw = None;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl VisitMut for CurrentColorVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CurrentColorVisitor::new(Options::default())),
+        current_color,
+        r##"<svg fill="none"><linearGradient id="a"><stop stopColor="#FF0000" /></linearGradient><path fill="#000" stroke="Red" d="M0 0h1" /><path fill="url(#a)" stroke={color} style="fill: #fff; stroke: transparent; opacity: 0.5" /><circle style={{ fill: "rgb(0, 0, 0)", stopColor: "#fff", strokeWidth: 2 }} /></svg>;"##,
+        r##"<svg fill="none"><linearGradient id="a"><stop stopColor="currentColor" /></linearGradient><path fill="currentColor" stroke="currentColor" d="M0 0h1" /><path fill="url(#a)" stroke={color} style="fill: currentColor; stroke: transparent; opacity: 0.5" /><circle style={{ fill: "currentColor", stopColor: "currentColor", strokeWidth: 2 }} /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(CurrentColorVisitor::new(Options {
+            keep: vec!["#FFF".to_string(), "red".to_string()]
+        })),
+        keep_colors,
+        r##"<svg><path fill="#ffffff" stroke="#000" /><path fill="RED" style="stroke: #fff" /></svg>;"##,
+        r##"<svg><path fill="#ffffff" stroke="currentColor" /><path fill="RED" style="stroke: #fff" /></svg>;"##
+    );
+}
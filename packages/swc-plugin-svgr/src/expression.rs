@@ -0,0 +1,99 @@
+use swc_core::{
+    common::{input::StringInput, BytePos, DUMMY_SP},
+    ecma::{
+        ast::*,
+        parser::{EsConfig, Parser, Syntax},
+        utils::is_valid_prop_ident,
+    },
+};
+
+/// Parses the JavaScript expression given as a `literal` option value. Source
+/// that parses to something else than an expression statement gives `None`.
+///
+/// Option values are almost always an identifier or a member chain such as
+/// `props.color`, which are built directly: spinning up the parser costs more
+/// than the rest of the transform for a small icon.
+pub(crate) fn parse_expression(source: &str) -> Result<Option<Box<Expr>>, ()> {
+    if let Some(expr) = member_chain(source) {
+        return Ok(Some(expr));
+    }
+
+    let mut parser = Parser::new(
+        Syntax::Es(EsConfig {
+            jsx: false,
+            fn_bind: false,
+            decorators: false,
+            decorators_before_export: false,
+            export_default_from: true,
+            import_assertions: false,
+            allow_return_outside_function: false,
+            allow_super_outside_method: false,
+            auto_accessors: false,
+        }),
+        StringInput::new(source, BytePos(0), BytePos(0)),
+        None,
+    );
+
+    match parser.parse_module() {
+        Ok(ast) if parser.take_errors().is_empty() => match ast.body.into_iter().next() {
+            Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))) => Ok(Some(expr)),
+            _ => Ok(None),
+        },
+        _ => Err(()),
+    }
+}
+
+/// `a` or `a.b.c`, where `a` is not a reserved word.
+fn member_chain(source: &str) -> Option<Box<Expr>> {
+    let mut parts = source.split('.');
+    let object = parts.next()?;
+    Ident::verify_symbol(object).ok()?;
+
+    let mut expr = Box::new(Expr::Ident(Ident::new(object.into(), DUMMY_SP)));
+    for prop in parts {
+        if !is_valid_prop_ident(prop) {
+            return None;
+        }
+        expr = Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: expr,
+            prop: MemberProp::Ident(Ident::new(prop.into(), DUMMY_SP)),
+        }));
+    }
+
+    Some(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_member_chains_without_parser() {
+        assert!(matches!(
+            member_chain("props.color").as_deref(),
+            Some(Expr::Member(MemberExpr {
+                prop: MemberProp::Ident(Ident { sym, .. }),
+                ..
+            })) if sym == "color"
+        ));
+        assert!(member_chain("true").is_none());
+        assert!(member_chain("props.").is_none());
+        assert!(member_chain("a + b").is_none());
+    }
+
+    #[test]
+    fn parse_other_expressions() {
+        assert!(matches!(
+            parse_expression("true").unwrap().as_deref(),
+            Some(Expr::Lit(Lit::Bool(Bool { value: true, .. })))
+        ));
+        assert!(matches!(
+            parse_expression("props.size || 24").unwrap().as_deref(),
+            Some(Expr::Bin(_))
+        ));
+        assert!(parse_expression("").unwrap().is_none());
+        assert!(parse_expression("{").is_err());
+        assert!(parse_expression("props.").is_err());
+    }
+}
@@ -0,0 +1,611 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use std::collections::HashSet;
+use swc_common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use crate::{
+    pipeline::{element_name, normalize, ElementPass},
+    references::{attribute_name, element_id, is_style, style_texts},
+};
+
+/// CSS properties that are also SVG presentation attributes, so that rules
+/// setting them can be inlined as attributes, which React Native supports.
+const PRESENTATION_ATTRIBUTES: [&str; 47] = [
+    "alignment-baseline",
+    "baseline-shift",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "color-interpolation-filters",
+    "direction",
+    "display",
+    "dominant-baseline",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "flood-color",
+    "flood-opacity",
+    "font-family",
+    "font-size",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "image-rendering",
+    "letter-spacing",
+    "lighting-color",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mask",
+    "opacity",
+    "overflow",
+    "paint-order",
+    "pointer-events",
+    "shape-rendering",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-decoration",
+    "visibility",
+    "word-spacing",
+];
+
+/// A compound selector such as `path.st0#a`, with a normalized type name.
+#[derive(Default)]
+struct Compound {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+/// Compound selectors joined by descendant combinators, from the outermost.
+type Selector = Vec<Compound>;
+
+struct Declaration {
+    property: String,
+    value: String,
+    important: bool,
+}
+
+/// A rule of a style sheet. `selectors` is `None` when the rule cannot be
+/// inlined: an at-rule, or a selector other than type, class and id
+/// selectors joined by descendant combinators.
+struct Rule {
+    text: String,
+    selectors: Option<Vec<Selector>>,
+    declarations: Vec<Declaration>,
+}
+
+/// What is known of an ancestor of the current element to match selectors.
+struct Ancestor {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+/// Inlines the rules of `<style>` elements into the elements they match, the
+/// way Illustrator exports style shapes with `.st0{fill:#fff}`: presentation
+/// properties become attributes, other properties and `!important` ones are
+/// added to the `style` attribute. The classes only used by inlined rules
+/// are removed, as is a `<style>` element left without rules.
+///
+/// Rules are inlined when their selectors are type, class and id selectors,
+/// possibly joined by descendant combinators. Other rules, such as `:hover`
+/// or `@media` ones, are kept in the `<style>` element.
+#[derive(Default)]
+pub struct InlineStylesVisitor {
+    /// Collected when entering the root element, in document order.
+    rules: Option<Vec<Rule>>,
+    /// Classes still used by the rules that are kept.
+    kept_classes: HashSet<String>,
+    ancestors: Vec<Ancestor>,
+}
+
+impl InlineStylesVisitor {
+    fn collect_rules(&mut self, jsx_element: &JSXElement, rules: &mut Vec<Rule>) {
+        let is_style = is_style(jsx_element);
+        for child in &jsx_element.children {
+            match child {
+                JSXElementChild::JSXElement(child) => self.collect_rules(child, rules),
+                _ if is_style => match style_text(child) {
+                    Some(css) => {
+                        for rule in parse_rules(css) {
+                            if rule.selectors.is_none() {
+                                self.kept_classes.extend(class_names(&rule.text));
+                            }
+                            rules.push(rule);
+                        }
+                    }
+                    None => {
+                        for css in style_texts(child) {
+                            self.kept_classes.extend(class_names(css));
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn matches(&self, selector: &Selector, element: &Ancestor) -> bool {
+        let (last, rest) = match selector.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+        if !compound_matches(last, element) {
+            return false;
+        }
+
+        let mut rest = rest.iter().rev().peekable();
+        for ancestor in self.ancestors.iter().rev() {
+            if rest
+                .peek()
+                .is_some_and(|compound| compound_matches(compound, ancestor))
+            {
+                rest.next();
+            }
+        }
+        rest.peek().is_none()
+    }
+
+    fn inline(&self, jsx_element: &mut JSXElement, element: &Ancestor) {
+        let rules = match &self.rules {
+            Some(rules) => rules,
+            None => return,
+        };
+
+        // The declarations of the matching selectors, by ascending precedence.
+        let mut matches = vec![];
+        let mut inlined_classes = HashSet::new();
+        for (order, rule) in rules.iter().enumerate() {
+            for selector in rule.selectors.iter().flatten() {
+                if self.matches(selector, element) {
+                    matches.push((specificity(selector), order, &rule.declarations));
+                    inlined_classes.extend(selector.iter().flat_map(|compound| &compound.classes));
+                }
+            }
+        }
+        if matches.is_empty() {
+            return;
+        }
+        matches.sort_by_key(|(specificity, order, _)| (*specificity, *order));
+
+        let mut attributes: Vec<(&str, &str)> = vec![];
+        let mut style: Vec<(&str, &str)> = vec![];
+        let mut important: Vec<(&str, &str)> = vec![];
+        for declaration in matches
+            .into_iter()
+            .flat_map(|(_, _, declarations)| declarations)
+        {
+            let declarations = if declaration.important {
+                &mut important
+            } else if PRESENTATION_ATTRIBUTES.contains(&declaration.property.as_str()) {
+                &mut attributes
+            } else {
+                &mut style
+            };
+            declarations.retain(|(property, _)| *property != declaration.property);
+            declarations.push((&declaration.property, &declaration.value));
+        }
+
+        for (property, value) in attributes {
+            set_attribute(jsx_element, property, value.to_string());
+        }
+        if !style.is_empty() || !important.is_empty() {
+            // Rules apply before the inline style, unless they are important.
+            let join = |declarations: Vec<(&str, &str)>| {
+                declarations
+                    .into_iter()
+                    .map(|(property, value)| format!("{}:{}", property, value))
+                    .collect::<Vec<_>>()
+            };
+            match attribute_value(jsx_element, "style") {
+                Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => {
+                    let mut declarations = join(style);
+                    declarations.extend(
+                        Some(value.trim().trim_end_matches(';').to_string())
+                            .filter(|value| !value.is_empty()),
+                    );
+                    declarations.extend(join(important));
+                    set_attribute(jsx_element, "style", declarations.join(";"));
+                }
+                // The style is an expression, which the rules cannot be
+                // merged into.
+                Some(_) => {}
+                None => {
+                    let mut declarations = join(style);
+                    declarations.extend(join(important));
+                    set_attribute(jsx_element, "style", declarations.join(";"));
+                }
+            }
+        }
+
+        remove_classes(jsx_element, |class| {
+            inlined_classes.contains(&class.to_string()) && !self.kept_classes.contains(class)
+        });
+    }
+}
+
+impl ElementPass for InlineStylesVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        if self.rules.is_none() {
+            let mut rules = vec![];
+            self.collect_rules(jsx_element, &mut rules);
+            self.rules = Some(rules);
+        }
+
+        let element = Ancestor {
+            name: element_name(jsx_element),
+            id: element_id(jsx_element).map(str::to_string),
+            classes: classes(jsx_element),
+        };
+        self.inline(jsx_element, &element);
+        self.ancestors.push(element);
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        self.ancestors.pop();
+
+        if is_style(jsx_element) {
+            for child in jsx_element.children.iter_mut() {
+                let kept = match style_text(child) {
+                    Some(css) => parse_rules(css)
+                        .into_iter()
+                        .filter(|rule| rule.selectors.is_none())
+                        .map(|rule| rule.text)
+                        .collect::<String>(),
+                    None => continue,
+                };
+                *child = JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    span: DUMMY_SP,
+                    expr: JSXExpr::Expr(Box::new(Expr::Lit(Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: kept.into(),
+                        raw: None,
+                    })))),
+                });
+            }
+        }
+
+        jsx_element.children.retain(|child| match child {
+            JSXElementChild::JSXElement(child) if is_style(child) => !child
+                .children
+                .iter()
+                .all(|child| style_text(child).is_some_and(|css| css.trim().is_empty())),
+            _ => true,
+        });
+    }
+}
+
+impl VisitMut for InlineStylesVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        self.enter_element(jsx_element);
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+/// The style sheet of a `<style>` child, unless it holds expressions.
+fn style_text(child: &JSXElementChild) -> Option<&str> {
+    match style_texts(child).as_slice() {
+        [css] => Some(css),
+        _ => None,
+    }
+}
+
+fn compound_matches(compound: &Compound, element: &Ancestor) -> bool {
+    compound
+        .name
+        .as_ref()
+        .is_none_or(|name| element.name.as_ref() == Some(name))
+        && compound
+            .id
+            .as_ref()
+            .is_none_or(|id| element.id.as_ref() == Some(id))
+        && compound
+            .classes
+            .iter()
+            .all(|class| element.classes.contains(class))
+}
+
+/// The `(ids, classes, types)` specificity of `selector`.
+fn specificity(selector: &Selector) -> (usize, usize, usize) {
+    selector
+        .iter()
+        .fold((0, 0, 0), |(ids, classes, types), compound| {
+            (
+                ids + usize::from(compound.id.is_some()),
+                classes + compound.classes.len(),
+                types + usize::from(compound.name.is_some()),
+            )
+        })
+}
+
+/// Splits `css` into rules, keeping the text of each so that the rules that
+/// are not inlined can be written back as they were.
+fn parse_rules(css: &str) -> Vec<Rule> {
+    let css = strip_comments(css);
+    let mut rules = vec![];
+    let mut rest = css.as_str();
+    while !rest.trim().is_empty() {
+        let open = rest.find(['{', ';']);
+        let (text, selectors, declarations) = match open {
+            // `@import url(a.css);` and stray semicolons.
+            Some(index) if rest.as_bytes()[index] == b';' => (&rest[..=index], None, vec![]),
+            Some(index) => {
+                let end = match block_end(rest, index) {
+                    Some(end) => end,
+                    None => {
+                        rules.push(unparsed(rest));
+                        break;
+                    }
+                };
+                let prelude = rest[..index].trim();
+                let selectors = if prelude.starts_with('@') {
+                    None
+                } else {
+                    prelude
+                        .split(',')
+                        .map(parse_selector)
+                        .collect::<Option<Vec<_>>>()
+                };
+                let declarations = parse_declarations(&rest[index + 1..end]);
+                (&rest[..=end], selectors, declarations)
+            }
+            None => {
+                rules.push(unparsed(rest));
+                break;
+            }
+        };
+
+        rules.push(Rule {
+            text: text.trim().to_string(),
+            selectors,
+            declarations,
+        });
+        rest = &rest[text.len()..];
+    }
+    rules
+}
+
+fn unparsed(text: &str) -> Rule {
+    Rule {
+        text: text.trim().to_string(),
+        selectors: None,
+        declarations: vec![],
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// The index of the `}` closing the block opened at `open`.
+fn block_end(css: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in css[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_selector(selector: &str) -> Option<Selector> {
+    let selector = selector
+        .split_ascii_whitespace()
+        .map(parse_compound)
+        .collect::<Option<Selector>>()?;
+    (!selector.is_empty()).then_some(selector)
+}
+
+fn parse_compound(compound: &str) -> Option<Compound> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut parsed = Compound::default();
+    let mut rest = compound;
+
+    let name_end = rest.find(|c: char| !is_name(c)).unwrap_or(rest.len());
+    if name_end > 0 {
+        parsed.name = Some(normalize(&rest[..name_end]));
+        rest = &rest[name_end..];
+    }
+    while let Some(prefix) = rest.chars().next() {
+        let end = rest[1..]
+            .find(|c: char| !is_name(c))
+            .map_or(rest.len(), |end| end + 1);
+        let name = &rest[1..end];
+        if name.is_empty() {
+            return None;
+        }
+        match prefix {
+            '.' => parsed.classes.push(name.to_string()),
+            '#' if parsed.id.is_none() => parsed.id = Some(name.to_string()),
+            _ => return None,
+        }
+        rest = &rest[end..];
+    }
+    Some(parsed)
+}
+
+fn parse_declarations(block: &str) -> Vec<Declaration> {
+    block
+        .split(';')
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let value = value.trim();
+            let (value, important) = match value.strip_suffix("!important") {
+                Some(value) => (value.trim_end(), true),
+                None => (value, false),
+            };
+            let property = property.trim().to_ascii_lowercase();
+            (!property.is_empty() && !value.is_empty()).then(|| Declaration {
+                property,
+                value: value.to_string(),
+                important,
+            })
+        })
+        .collect()
+}
+
+/// The class names a style sheet mentions, or more.
+fn class_names(css: &str) -> impl Iterator<Item = String> + '_ {
+    css.split('.').skip(1).filter_map(|rest| {
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        (end > 0).then(|| rest[..end].to_string())
+    })
+}
+
+fn is_class(name: &JSXAttrName) -> bool {
+    matches!(attribute_name(name).as_str(), "class" | "classname")
+}
+
+fn classes(jsx_element: &JSXElement) -> Vec<String> {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name,
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+                ..
+            }) if is_class(name) => Some(value.split_ascii_whitespace().map(str::to_string)),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Removes the classes matching `remove`, and the class attribute when none
+/// is left.
+fn remove_classes(jsx_element: &mut JSXElement, remove: impl Fn(&str) -> bool) {
+    jsx_element.opening.attrs.retain_mut(|attr| match attr {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name,
+            value: Some(JSXAttrValue::Lit(Lit::Str(str))),
+            ..
+        }) if is_class(name) => {
+            let classes = str
+                .value
+                .split_ascii_whitespace()
+                .filter(|class| !remove(class))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if classes.len() != str.value.len() {
+                str.value = classes.into();
+                str.raw = None;
+            }
+            !str.value.is_empty()
+        }
+        _ => true,
+    });
+}
+
+fn attribute_value<'a>(jsx_element: &'a JSXElement, name: &str) -> Option<&'a JSXAttrValue> {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: attr_name,
+                value: Some(value),
+                ..
+            }) if attribute_name(attr_name) == normalize(name) => Some(value),
+            _ => None,
+        })
+}
+
+/// Sets the attribute `name`, whether it is written `fill-opacity` or
+/// `fillOpacity`.
+fn set_attribute(jsx_element: &mut JSXElement, name: &str, value: String) {
+    let value = Some(JSXAttrValue::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    })));
+    let existing = jsx_element
+        .opening
+        .attrs
+        .iter_mut()
+        .find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(attr) if attribute_name(&attr.name) == normalize(name) => {
+                Some(attr)
+            }
+            _ => None,
+        });
+    match existing {
+        Some(attr) => attr.value = value,
+        None => jsx_element
+            .opening
+            .attrs
+            .push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span: DUMMY_SP,
+                name: JSXAttrName::Ident(Ident::new(name.into(), DUMMY_SP)),
+                value,
+            })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(InlineStylesVisitor::default()),
+        inline_styles,
+        r##"<svg><style>{"/* Illustrator */ .st0{fill:#FFFFFF;} .st1 , #b{fill:none;stroke:#000;stroke-width:2} g .st1{stroke-opacity:.5;mix-blend-mode:multiply} path{fill:red!important}"}</style><path className="st0" fill="blue" d="M0 0h1" /><g><path className="st1 icon" style="opacity: 0.5" d="M0 0h2" /></g><circle id="b" r="1" /></svg>;"##,
+        r##"<svg><path fill="#FFFFFF" d="M0 0h1" style="fill:red" /><g><path className="icon" style="mix-blend-mode:multiply;opacity: 0.5;fill:red" d="M0 0h2" fill="none" stroke="#000" stroke-width="2" stroke-opacity=".5" /></g><circle id="b" r="1" fill="none" stroke="#000" stroke-width="2" /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(InlineStylesVisitor::default()),
+        keep_rules_that_cannot_be_inlined,
+        r##"<svg><style>{".a{fill:red} .a:hover{fill:blue} @media (prefers-color-scheme: dark){.b{fill:#fff}}"}</style><path class="a b" d="M0 0h1" /></svg>;"##,
+        r##"<svg><style>{".a:hover{fill:blue}@media (prefers-color-scheme: dark){.b{fill:#fff}}"}</style><path class="a b" d="M0 0h1" fill="red" /></svg>;"##
+    );
+}
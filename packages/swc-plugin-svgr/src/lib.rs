@@ -1,108 +1,127 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use swc_core::{
-    ecma::{ast::*, visit::VisitMutWith},
-    plugin::{plugin_transform, proxies::TransformPluginProgramMetadata},
+    ecma::ast::*,
+    plugin::{
+        plugin_transform,
+        proxies::{PluginCommentsProxy, TransformPluginProgramMetadata},
+    },
 };
 
-use crate::transform_attribute::TransformAttributeVisitor;
-use add_jsx_attribute::{AddJSXAttributeVisitor, Options as AddJSXAttributeOptions};
-use remove_jsx_attribute::{Options as RemoveAttributeOptions, RemoveJSXAttributeVisitor};
-use remove_jsx_empty_expression::RemoveEmptyExpressionVisitor;
-use svg_em_dimensions::{Options as SVGEmDimensionsOptions, SVGEmDimensionsVisitor};
+use add_jsx_attribute::Options as AddJSXAttributeOptions;
+use cleanup::Options as CleanupOptions;
+use cleanup_numeric_values::Options as CleanupNumericValuesOptions;
+use collapse_groups::Options as CollapseGroupsOptions;
+use css_variables::Options as CssVariablesOptions;
+use current_color::Options as CurrentColorOptions;
+use convert_shapes::Options as ConvertShapesOptions;
+use minify_path_data::Options as MinifyPathDataOptions;
+use parse_svg::{parse_svg, ParseSvgError};
+use prefix_ids::Options as PrefixIdsOptions;
+use registry::PassRegistry;
+use remove_jsx_attribute::Options as RemoveAttributeOptions;
+use remove_unused_ids::Options as RemoveUnusedIdsOptions;
+use replace_jsx_attribute_value::Options as ReplaceAttributeValueOptions;
+use svg_em_dimensions::Options as SVGEmDimensionsOptions;
+use svgr_config::SvgrOptions;
 use transform_svg_component::{
-    config::Config as TransformSVGComponentOptions, TransformSVGComponentVisitor,
-};
-
-use replace_jsx_attribute_value::{
-    Options as ReplaceAttributeValueOptions, ReplaceJSXAttributeValueVisitor,
-};
-use svg_dynamic_title::{Options as DynamicTitleOptions, DynamicTitleVisitor};
-use transform_react_native_svg::{
-    TransformReactNativeSVGVisitor
+    config::Config as TransformSVGComponentOptions,
+    error::{parse_config, ConfigError},
 };
 
 pub mod add_jsx_attribute;
+pub mod cleanup;
+pub mod cleanup_numeric_values;
+pub mod collapse_groups;
+pub mod convert_shapes;
+pub mod css_variables;
+pub mod current_color;
+mod expression;
+pub mod inline_styles;
+pub mod minify_path_data;
+pub mod parse_svg;
+mod path_data;
+pub mod prefix_ids;
+pub mod pipeline;
+mod references;
+pub mod registry;
+pub mod remove_hidden_elements;
 pub mod remove_jsx_attribute;
 pub mod remove_jsx_empty_expression;
+pub mod remove_unused_ids;
 pub mod replace_jsx_attribute_value;
 pub mod svg_dynamic_title;
 pub mod svg_em_dimensions;
+pub mod svgr_config;
 pub mod transform_attribute;
 pub mod transform_react_native_svg;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
-    transform_svg_component: Option<TransformSVGComponentOptions>,
-    em_dimensions: Option<SVGEmDimensionsOptions>,
-    remove_jsx_attribute: Option<RemoveAttributeOptions>,
-    add_jsx_attribute: Option<AddJSXAttributeOptions>,
-    replace_attribute_values: Option<ReplaceAttributeValueOptions>,
-    title_prop: bool,
-    desc_prop: bool,
-    native: bool,
+    pub transform_svg_component: Option<TransformSVGComponentOptions>,
+    pub cleanup: Option<CleanupOptions>,
+    #[serde(default)]
+    pub inline_styles: bool,
+    #[serde(default)]
+    pub remove_hidden_elements: bool,
+    pub remove_unused_ids: Option<RemoveUnusedIdsOptions>,
+    pub prefix_ids: Option<PrefixIdsOptions>,
+    pub convert_shapes: Option<ConvertShapesOptions>,
+    pub minify_path_data: Option<MinifyPathDataOptions>,
+    pub cleanup_numeric_values: Option<CleanupNumericValuesOptions>,
+    pub collapse_groups: Option<CollapseGroupsOptions>,
+    pub em_dimensions: Option<SVGEmDimensionsOptions>,
+    pub remove_jsx_attribute: Option<RemoveAttributeOptions>,
+    pub add_jsx_attribute: Option<AddJSXAttributeOptions>,
+    pub replace_attribute_values: Option<ReplaceAttributeValueOptions>,
+    pub current_color: Option<CurrentColorOptions>,
+    pub css_variables: Option<CssVariablesOptions>,
+    pub title_prop: bool,
+    pub desc_prop: bool,
+    pub native: bool,
+    /// Names of the passes to run, in order, instead of the default pipeline.
+    pub pipeline: Option<Vec<String>>,
+    /// Config sections of the passes registered by downstream crates, keyed
+    /// by pass name.
+    #[serde(flatten)]
+    pub custom: Map<String, Value>,
 }
 
 #[plugin_transform]
-pub fn process_transform(
-    mut program: Program,
-    metadata: TransformPluginProgramMetadata,
-) -> Program {
-    let options = serde_json::from_str::<Options>(
-        &metadata
-            .get_transform_plugin_config()
-            .expect("failed to get plugin config for swc-plugin-svgr"),
-    )
-    .expect("invalid config for remove-jsx-attribute");
-
-    if let Some(transform_svg_component_options) = options.transform_svg_component {
-        program.visit_mut_with(&mut TransformSVGComponentVisitor::new(
-            transform_svg_component_options,
-        ));
-    }
-
-    if let Some(em_dimensions_options) = options.em_dimensions {
-        program.visit_mut_with(&mut SVGEmDimensionsVisitor::new(em_dimensions_options));
-    }
-
-    if let Some(remove_attribute_options) = options.remove_jsx_attribute {
-        program.visit_mut_with(&mut RemoveJSXAttributeVisitor {
-            options: remove_attribute_options,
-        });
-    }
-
-    if let Some(add_attribute_options) = options.add_jsx_attribute {
-        program.visit_mut_with(&mut AddJSXAttributeVisitor {
-            options: add_attribute_options,
-        });
-    }
-
-    program.visit_mut_with(&mut RemoveEmptyExpressionVisitor);
-
-    if let Some(replace_attribute_values_options) = options.replace_attribute_values {
-        program.visit_mut_with(&mut ReplaceJSXAttributeValueVisitor {
-            options: replace_attribute_values_options,
-        });
-    }
-
-    if options.title_prop {
-        program.visit_mut_with(&mut DynamicTitleVisitor::new(DynamicTitleOptions {
-            tag: "title".into(),
-        }));
-    }
-
-    if options.desc_prop {
-        program.visit_mut_with(&mut DynamicTitleVisitor::new(DynamicTitleOptions {
-            tag: "desc".into(),
-        }));
-    }
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    PassRegistry::with_builtin_passes().process_transform(program, metadata)
+}
 
-    if options.native {
-        program
-            .visit_mut_with(&mut TransformReactNativeSVGVisitor {comments: metadata.comments});
+/// Parses the plugin config, given either as `Options` or in the shape of an
+/// SVGR plugin call, `{ config, state }`, which is mapped to `Options`.
+pub fn parse_options(json: &str) -> Result<Options, ConfigError> {
+    let is_svgr_config = serde_json::from_str::<Value>(json)
+        .map(|value| value.get("config").is_some_and(Value::is_object))
+        .unwrap_or(false);
+
+    if is_svgr_config {
+        parse_config::<SvgrOptions>(json).and_then(SvgrOptions::into_options)
+    } else {
+        parse_config::<Options>(json)
     }
+}
 
-    program.visit_mut_with(&mut TransformAttributeVisitor);
+/// Parses raw SVG source and runs the whole pass pipeline on it, so the plugin
+/// can be used from Rust without a JavaScript host.
+pub fn transform_svg(
+    source: &str,
+    options: Options,
+) -> Result<(Program, Vec<ConfigError>), ParseSvgError> {
+    Ok(transform(parse_svg(source)?, options, None))
+}
 
-    program
+/// Runs every enabled pass on `program` and returns the config errors the
+/// passes recovered from, with their key paths relative to `Options`.
+pub fn transform(
+    program: Program,
+    options: Options,
+    comments: Option<PluginCommentsProxy>,
+) -> (Program, Vec<ConfigError>) {
+    PassRegistry::with_builtin_passes().transform(program, &options, comments)
 }
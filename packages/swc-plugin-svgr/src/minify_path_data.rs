@@ -0,0 +1,142 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::error::ConfigError;
+
+use crate::{
+    path_data::{clamp_precision, minify_path, parse_path},
+    pipeline::ElementPass,
+};
+
+fn default_precision() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Options {
+    /// Number of decimals kept in coordinates, at most 15.
+    #[serde(default = "default_precision")]
+    pub precision: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            precision: default_precision(),
+        }
+    }
+}
+
+/// Rewrites the `d` attribute of `<path>` elements in its shortest form. Path
+/// data that does not parse is left as is.
+pub struct MinifyPathDataVisitor {
+    options: Options,
+    errors: Vec<ConfigError>,
+}
+
+impl MinifyPathDataVisitor {
+    pub fn new(mut options: Options) -> Self {
+        let (precision, err) = clamp_precision(options.precision);
+        options.precision = precision;
+        MinifyPathDataVisitor {
+            options,
+            errors: err.into_iter().collect(),
+        }
+    }
+}
+
+impl ElementPass for MinifyPathDataVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        if !matches!(&jsx_element.opening.name, JSXElementName::Ident(Ident { sym, .. }) if sym == "path")
+        {
+            return;
+        }
+
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(str))),
+                ..
+            }) = attr
+            {
+                if sym != "d" {
+                    continue;
+                }
+
+                if let Ok(segments) = parse_path(&str.value) {
+                    let d = minify_path(&segments, self.options.precision);
+                    if d.len() <= str.value.len() {
+                        str.value = d.into();
+                        str.raw = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for MinifyPathDataVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(MinifyPathDataVisitor::new(Options::default())),
+        minify_path_data,
+        r#"<svg><path d="M 10.000 20.000 L 10 30 L 25 30 L 10 20 Z" /><rect d="M 0 0 L 1 1" /></svg>;"#,
+        r#"<svg><path d="M10 20v10h15z" /><rect d="M 0 0 L 1 1" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(MinifyPathDataVisitor::new(Options { precision: 1 })),
+        keep_invalid_path_data,
+        r#"<svg><path d="M0.123 0.456 L1.5 1.25" /><path d="L 10 10 M 20 20" /></svg>;"#,
+        r#"<svg><path d="M.1.5l1.4.8" /><path d="L 10 10 M 20 20" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(MinifyPathDataVisitor::new(Options { precision: 400 })),
+        clamp_large_precision,
+        r#"<svg><path d="M 0.5 0.25 L 1.5 1.25" /></svg>;"#,
+        r#"<svg><path d="M.5.25l1 1" /></svg>;"#
+    );
+
+    #[test]
+    fn report_too_large_precision() {
+        let errors = MinifyPathDataVisitor::new(Options { precision: 400 }).take_errors();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "precision");
+        assert_eq!(errors[0].value.as_deref(), Some("400"));
+    }
+}
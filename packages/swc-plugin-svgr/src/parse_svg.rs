@@ -0,0 +1,542 @@
+use std::{collections::HashMap, fmt};
+use swc_common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+use xmlparser::{ElementEnd, EntityDefinition, StrSpan, Token, Tokenizer};
+
+/// Error raised when the SVG source is not well-formed XML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseSvgError {
+    pub message: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for ParseSvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse SVG at {}:{}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseSvgError {}
+
+struct OpenElement {
+    element: JSXElement,
+    prefixes: Vec<String>,
+    preserve_space: bool,
+}
+
+struct SvgParser<'a> {
+    source: &'a str,
+    entities: HashMap<String, String>,
+    stack: Vec<OpenElement>,
+    root: Option<JSXElement>,
+}
+
+/// Parses SVG source into the program shape `parseSync` produces for the same
+/// markup: a module holding a single JSX expression statement.
+pub fn parse_svg(source: &str) -> Result<Program, ParseSvgError> {
+    let element = parse_svg_element(source)?;
+
+    Ok(Program::Module(Module {
+        span: DUMMY_SP,
+        body: vec![ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::JSXElement(Box::new(element))),
+        }))],
+        shebang: None,
+    }))
+}
+
+/// Parses SVG source into the JSX element of its root node.
+///
+/// Comments, processing instructions and the doctype are dropped, entities and
+/// CDATA sections are resolved to text, and whitespace-only text between
+/// elements is removed unless `xml:space="preserve"` is in effect.
+pub fn parse_svg_element(source: &str) -> Result<JSXElement, ParseSvgError> {
+    let mut parser = SvgParser {
+        source,
+        entities: HashMap::new(),
+        stack: vec![],
+        root: None,
+    };
+
+    for token in Tokenizer::from(source) {
+        let token = token.map_err(|err| {
+            let pos = err.pos();
+            ParseSvgError {
+                message: err.to_string(),
+                line: pos.row,
+                column: pos.col,
+            }
+        })?;
+        parser.handle_token(token)?;
+    }
+
+    if let Some(open) = parser.stack.last() {
+        return Err(parser.error(
+            source.len(),
+            format!(
+                "unclosed element <{}>",
+                element_name_to_string(&open.element.opening.name)
+            ),
+        ));
+    }
+
+    match parser.root.take() {
+        Some(root) => Ok(root),
+        None => Err(parser.error(source.len(), "no root element found".into())),
+    }
+}
+
+impl<'a> SvgParser<'a> {
+    fn handle_token(&mut self, token: Token<'a>) -> Result<(), ParseSvgError> {
+        match token {
+            Token::EntityDeclaration {
+                name,
+                definition: EntityDefinition::EntityValue(value),
+                ..
+            } => {
+                self.entities
+                    .insert(name.as_str().to_string(), value.as_str().to_string());
+            }
+            Token::ElementStart {
+                prefix,
+                local,
+                span,
+            } => {
+                if self.stack.is_empty() && self.root.is_some() {
+                    return Err(self.error(span.start(), "found more than one root element".into()));
+                }
+
+                let preserve_space = self
+                    .stack
+                    .last()
+                    .is_some_and(|parent| parent.preserve_space);
+                let prefixes = self
+                    .stack
+                    .last()
+                    .map_or_else(Vec::new, |parent| parent.prefixes.clone());
+
+                self.stack.push(OpenElement {
+                    element: JSXElement {
+                        span: DUMMY_SP,
+                        opening: JSXOpeningElement {
+                            name: create_element_name(prefix.as_str(), local.as_str()),
+                            span: DUMMY_SP,
+                            attrs: vec![],
+                            self_closing: false,
+                            type_args: None,
+                        },
+                        children: vec![],
+                        closing: None,
+                    },
+                    prefixes,
+                    preserve_space,
+                });
+            }
+            Token::Attribute {
+                prefix,
+                local,
+                value,
+                span,
+            } => {
+                let value = self.decode_text(value)?;
+                let open = self
+                    .stack
+                    .last_mut()
+                    .expect("attribute outside of an element");
+
+                if prefix.as_str() == "xmlns" {
+                    open.prefixes.push(local.as_str().to_string());
+                } else if prefix.as_str() == "xml" && local.as_str() == "space" {
+                    open.preserve_space = value == "preserve";
+                }
+
+                if open
+                    .element
+                    .opening
+                    .attrs
+                    .iter()
+                    .any(|attr| is_attribute_named(attr, prefix.as_str(), local.as_str()))
+                {
+                    return Err(self.error(
+                        span.start(),
+                        format!("duplicate attribute \"{}\"", span_name(prefix, local)),
+                    ));
+                }
+
+                open.element
+                    .opening
+                    .attrs
+                    .push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                        span: DUMMY_SP,
+                        name: create_attribute_name(prefix.as_str(), local.as_str()),
+                        value: Some(create_attribute_value(value)),
+                    }));
+            }
+            Token::ElementEnd { end, span } => match end {
+                ElementEnd::Open => self.check_prefixes(span.start())?,
+                ElementEnd::Empty => {
+                    self.check_prefixes(span.start())?;
+                    let mut open = self.stack.pop().expect("element end without start");
+                    open.element.opening.self_closing = true;
+                    self.append_element(open.element);
+                }
+                ElementEnd::Close(prefix, local) => {
+                    let open = self.stack.pop().ok_or_else(|| {
+                        self.error(
+                            span.start(),
+                            format!("unexpected closing tag </{}>", span_name(prefix, local)),
+                        )
+                    })?;
+                    let name = create_element_name(prefix.as_str(), local.as_str());
+
+                    if element_name_to_string(&open.element.opening.name)
+                        != element_name_to_string(&name)
+                    {
+                        return Err(self.error(
+                            span.start(),
+                            format!(
+                                "expected </{}>, found </{}>",
+                                element_name_to_string(&open.element.opening.name),
+                                span_name(prefix, local)
+                            ),
+                        ));
+                    }
+
+                    let mut element = open.element;
+                    element.closing = Some(JSXClosingElement {
+                        span: DUMMY_SP,
+                        name,
+                    });
+                    self.append_element(element);
+                }
+            },
+            Token::Text { text } => {
+                let value = self.decode_text(text)?;
+                self.append_text(text, value)?;
+            }
+            Token::Cdata { text, .. } => {
+                self.append_text(text, text.as_str().to_string())?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn append_element(&mut self, element: JSXElement) {
+        if let Some(parent) = self.stack.last_mut() {
+            parent
+                .element
+                .children
+                .push(JSXElementChild::JSXElement(Box::new(element)));
+        } else {
+            self.root = Some(element);
+        }
+    }
+
+    fn append_text(&mut self, span: StrSpan<'a>, value: String) -> Result<(), ParseSvgError> {
+        let parent = match self.stack.last_mut() {
+            Some(parent) => parent,
+            None if value.trim().is_empty() => return Ok(()),
+            None => {
+                return Err(self.error(
+                    span.start(),
+                    "text found outside of the root element".into(),
+                ))
+            }
+        };
+
+        if value.trim().is_empty() && !parent.preserve_space {
+            return Ok(());
+        }
+
+        parent.element.children.push(create_text_child(value));
+
+        Ok(())
+    }
+
+    fn check_prefixes(&self, pos: usize) -> Result<(), ParseSvgError> {
+        let open = self.stack.last().expect("element end without start");
+        let is_declared =
+            |prefix: &str| prefix == "xml" || open.prefixes.iter().any(|p| p == prefix);
+
+        if let JSXElementName::JSXNamespacedName(JSXNamespacedName { ns, .. }) =
+            &open.element.opening.name
+        {
+            if !is_declared(&ns.sym) {
+                return Err(self.error(pos, format!("undeclared namespace prefix \"{}\"", ns.sym)));
+            }
+        }
+
+        for attr in &open.element.opening.attrs {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, .. }),
+                ..
+            }) = attr
+            {
+                if ns.sym != *"xmlns" && !is_declared(&ns.sym) {
+                    return Err(
+                        self.error(pos, format!("undeclared namespace prefix \"{}\"", ns.sym))
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves predefined, numeric and DTD-declared entity references.
+    fn decode_text(&self, text: StrSpan<'a>) -> Result<String, ParseSvgError> {
+        let raw = text.as_str();
+        let mut decoded = String::with_capacity(raw.len());
+        let mut rest = raw;
+
+        while let Some(start) = rest.find('&') {
+            decoded.push_str(&rest[..start]);
+            let end = rest[start..].find(';').ok_or_else(|| {
+                self.error(
+                    text.start() + raw.len() - rest.len() + start,
+                    "unterminated entity reference".into(),
+                )
+            })? + start;
+            let entity = &rest[start + 1..end];
+
+            let resolved = match entity {
+                "lt" => Some("<".to_string()),
+                "gt" => Some(">".to_string()),
+                "amp" => Some("&".to_string()),
+                "apos" => Some("'".to_string()),
+                "quot" => Some("\"".to_string()),
+                _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from),
+                _ if entity.starts_with('#') => entity[1..]
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(String::from),
+                _ => self.entities.get(entity).cloned(),
+            };
+
+            match resolved {
+                Some(value) => decoded.push_str(&value),
+                None => {
+                    return Err(self.error(
+                        text.start() + raw.len() - rest.len() + start,
+                        format!("unknown entity \"&{};\"", entity),
+                    ))
+                }
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        decoded.push_str(rest);
+
+        Ok(decoded)
+    }
+
+    fn error(&self, pos: usize, message: String) -> ParseSvgError {
+        let before = &self.source[..pos.min(self.source.len())];
+        let line = before.matches('\n').count() as u32 + 1;
+        let column = before
+            .rfind('\n')
+            .map_or(before.chars().count(), |i| before[i + 1..].chars().count())
+            as u32
+            + 1;
+
+        ParseSvgError {
+            message,
+            line,
+            column,
+        }
+    }
+}
+
+fn span_name(prefix: StrSpan, local: StrSpan) -> String {
+    if prefix.as_str().is_empty() {
+        local.as_str().to_string()
+    } else {
+        format!("{}:{}", prefix.as_str(), local.as_str())
+    }
+}
+
+fn create_element_name(prefix: &str, local: &str) -> JSXElementName {
+    if prefix.is_empty() {
+        JSXElementName::Ident(Ident::new(local.into(), DUMMY_SP))
+    } else {
+        JSXElementName::JSXNamespacedName(JSXNamespacedName {
+            ns: Ident::new(prefix.into(), DUMMY_SP),
+            name: Ident::new(local.into(), DUMMY_SP),
+        })
+    }
+}
+
+fn create_attribute_name(prefix: &str, local: &str) -> JSXAttrName {
+    if prefix.is_empty() {
+        JSXAttrName::Ident(Ident::new(local.into(), DUMMY_SP))
+    } else {
+        JSXAttrName::JSXNamespacedName(JSXNamespacedName {
+            ns: Ident::new(prefix.into(), DUMMY_SP),
+            name: Ident::new(local.into(), DUMMY_SP),
+        })
+    }
+}
+
+fn is_attribute_named(attr: &JSXAttrOrSpread, prefix: &str, local: &str) -> bool {
+    match attr {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            ..
+        }) => prefix.is_empty() && sym == local,
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }),
+            ..
+        }) => ns.sym == *prefix && name.sym == *local,
+        _ => false,
+    }
+}
+
+fn element_name_to_string(name: &JSXElementName) -> String {
+    match name {
+        JSXElementName::Ident(Ident { sym, .. }) => sym.to_string(),
+        JSXElementName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+            format!("{}:{}", ns.sym, name.sym)
+        }
+        JSXElementName::JSXMemberExpr(_) => String::new(),
+    }
+}
+
+/// JSX string attributes cannot escape quotes, so values containing them are
+/// emitted as string expressions instead.
+fn create_attribute_value(value: String) -> JSXAttrValue {
+    let str_lit = Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.as_str().into(),
+        raw: None,
+    });
+
+    if value.contains('"') {
+        JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            span: DUMMY_SP,
+            expr: JSXExpr::Expr(Box::new(Expr::Lit(str_lit))),
+        })
+    } else {
+        JSXAttrValue::Lit(str_lit)
+    }
+}
+
+/// Text that JSX would reinterpret (braces, angle brackets, ampersands, line
+/// breaks or surrounding whitespace) is wrapped in a string expression to keep it verbatim.
+fn create_text_child(value: String) -> JSXElementChild {
+    let needs_expression =
+        value.contains(['{', '}', '<', '>', '&', '\n', '\r']) || value.trim() != value;
+
+    if needs_expression {
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+            span: DUMMY_SP,
+            expr: JSXExpr::Expr(Box::new(Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: value.into(),
+                raw: None,
+            })))),
+        })
+    } else {
+        JSXElementChild::JSXText(JSXText {
+            span: DUMMY_SP,
+            value: value.as_str().into(),
+            raw: value.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::{
+        common::{sync::Lrc, SourceMap},
+        ecma::codegen::{text_writer::JsWriter, Emitter},
+    };
+
+    fn print(program: &Program) -> String {
+        let cm: Lrc<SourceMap> = Default::default();
+        let mut buf = vec![];
+        {
+            let mut emitter = Emitter {
+                cfg: Default::default(),
+                cm: cm.clone(),
+                comments: None,
+                wr: JsWriter::new(cm, "\n", &mut buf, None),
+            };
+            emitter.emit_program(program).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn parse_svg_into_jsx() {
+        let program = parse_svg(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Generator: Sketch 46.2 (44496) -->
+<svg width="48px" viewBox="0 0 48 1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+    <title>Rectangle 5</title>
+    <defs></defs>
+    <g id="Page-1" stroke-width="1"><use xlink:href="#a"/></g>
+</svg>"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            print(&program),
+            "<svg width=\"48px\" viewBox=\"0 0 48 1\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\"><title>Rectangle 5</title><defs></defs><g id=\"Page-1\" stroke-width=\"1\"><use xlink:href=\"#a\"/></g></svg>;\n"
+        );
+    }
+
+    #[test]
+    fn resolve_entities_and_cdata() {
+        let program = parse_svg(
+            r#"<!DOCTYPE svg [<!ENTITY brand "Acme">]>
+<svg><title>&brand; &#x263A;</title><desc>AT&amp;T &lt;3</desc><style><![CDATA[.a{fill:#fff}]]></style></svg>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            print(&program),
+            "<svg><title>Acme \u{263A}</title><desc>{\"AT&T <3\"}</desc><style>{\".a{fill:#fff}\"}</style></svg>;\n"
+        );
+    }
+
+    #[test]
+    fn preserve_whitespace_when_requested() {
+        let program =
+            parse_svg(r#"<svg><text xml:space="preserve"> a </text><text> b </text></svg>"#)
+                .unwrap();
+
+        assert_eq!(
+            print(&program),
+            "<svg><text xml:space=\"preserve\">{\" a \"}</text><text>{\" b \"}</text></svg>;\n"
+        );
+    }
+
+    #[test]
+    fn report_mismatched_tags() {
+        let err = parse_svg("<svg>\n  <g></path>\n</svg>").unwrap_err();
+
+        assert_eq!(err.message, "expected </g>, found </path>");
+        assert_eq!((err.line, err.column), (2, 6));
+    }
+
+    #[test]
+    fn report_undeclared_prefix() {
+        let err = parse_svg(r#"<svg sketch:type="MSPage"/>"#).unwrap_err();
+
+        assert_eq!(err.message, "undeclared namespace prefix \"sketch\"");
+    }
+}
@@ -0,0 +1,615 @@
+use transform_svg_component::error::ConfigError;
+
+pub(crate) type Point = (f64, f64);
+
+/// A box as its `(min, max)` corners.
+pub(crate) type Bounds = (Point, Point);
+
+/// A path command with absolute coordinates. Shorthands are expanded when
+/// parsing: `H` and `V` give `LineTo`, `S` gives `CurveTo` and `T` `QuadTo`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Segment {
+    MoveTo(Point),
+    LineTo(Point),
+    CurveTo(Point, Point, Point),
+    QuadTo(Point, Point),
+    Arc {
+        radii: Point,
+        rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    },
+    Close,
+}
+
+impl Segment {
+    fn end(&self) -> Option<Point> {
+        match *self {
+            Segment::MoveTo(to)
+            | Segment::LineTo(to)
+            | Segment::CurveTo(_, _, to)
+            | Segment::QuadTo(_, to)
+            | Segment::Arc { to, .. } => Some(to),
+            Segment::Close => None,
+        }
+    }
+
+    /// Whether the segment draws nothing when it starts at `from`.
+    fn is_zero_length(&self, from: Point) -> bool {
+        match *self {
+            Segment::LineTo(to) | Segment::Arc { to, .. } => to == from,
+            Segment::CurveTo(c1, c2, to) => c1 == from && c2 == from && to == from,
+            Segment::QuadTo(c, to) => c == from && to == from,
+            Segment::MoveTo(_) | Segment::Close => false,
+        }
+    }
+
+    fn round(self, precision: u32) -> Self {
+        let round_point = |point| round_point(point, precision);
+        match self {
+            Segment::MoveTo(to) => Segment::MoveTo(round_point(to)),
+            Segment::LineTo(to) => Segment::LineTo(round_point(to)),
+            Segment::CurveTo(c1, c2, to) => {
+                Segment::CurveTo(round_point(c1), round_point(c2), round_point(to))
+            }
+            Segment::QuadTo(c, to) => Segment::QuadTo(round_point(c), round_point(to)),
+            Segment::Arc {
+                radii,
+                rotation,
+                large_arc,
+                sweep,
+                to,
+            } => Segment::Arc {
+                radii: round_point(radii),
+                rotation: round(rotation, precision),
+                large_arc,
+                sweep,
+                to: round_point(to),
+            },
+            Segment::Close => Segment::Close,
+        }
+    }
+}
+
+/// Parses the `d` attribute of a path. Unlike browsers, which render the path
+/// up to the first error, invalid data is rejected as a whole.
+pub(crate) fn parse_path(d: &str) -> Result<Vec<Segment>, ()> {
+    let mut lexer = Lexer {
+        bytes: d.as_bytes(),
+        pos: 0,
+    };
+    let mut segments = vec![];
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    let mut command = None;
+
+    while !lexer.at_end() {
+        let letter = match lexer.command() {
+            Some(letter) => letter,
+            // Numbers after a command repeat it, and after a moveto, draw lines.
+            None => match command {
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(letter) if letter != b'Z' && letter != b'z' => letter,
+                _ => return Err(()),
+            },
+        };
+        if segments.is_empty() && letter != b'M' && letter != b'm' {
+            return Err(());
+        }
+        command = Some(letter);
+
+        let origin = if letter.is_ascii_lowercase() {
+            current
+        } else {
+            (0.0, 0.0)
+        };
+        let point = |lexer: &mut Lexer| -> Result<Point, ()> {
+            Ok((lexer.number()? + origin.0, lexer.number()? + origin.1))
+        };
+
+        let segment = match letter.to_ascii_uppercase() {
+            b'M' => {
+                let to = point(&mut lexer)?;
+                start = to;
+                Segment::MoveTo(to)
+            }
+            b'L' => Segment::LineTo(point(&mut lexer)?),
+            b'H' => Segment::LineTo((lexer.number()? + origin.0, current.1)),
+            b'V' => Segment::LineTo((current.0, lexer.number()? + origin.1)),
+            b'C' => Segment::CurveTo(point(&mut lexer)?, point(&mut lexer)?, point(&mut lexer)?),
+            b'S' => {
+                let c1 = reflect(segments.last(), current, true);
+                Segment::CurveTo(c1, point(&mut lexer)?, point(&mut lexer)?)
+            }
+            b'Q' => Segment::QuadTo(point(&mut lexer)?, point(&mut lexer)?),
+            b'T' => {
+                let c = reflect(segments.last(), current, false);
+                Segment::QuadTo(c, point(&mut lexer)?)
+            }
+            b'A' => Segment::Arc {
+                radii: (lexer.number()?.abs(), lexer.number()?.abs()),
+                rotation: lexer.number()?,
+                large_arc: lexer.flag()?,
+                sweep: lexer.flag()?,
+                to: point(&mut lexer)?,
+            },
+            b'Z' => Segment::Close,
+            _ => return Err(()),
+        };
+
+        current = segment.end().unwrap_or(start);
+        segments.push(segment);
+    }
+
+    Ok(segments)
+}
+
+/// Writes `segments` in their shortest form, with numbers rounded to
+/// `precision` decimals. For each command, the shorter of the absolute and
+/// relative forms is picked, and segments that draw nothing are dropped.
+pub(crate) fn minify_path(segments: &[Segment], precision: u32) -> String {
+    let segments = segments
+        .iter()
+        .map(|segment| segment.round(precision))
+        .collect::<Vec<_>>();
+    let segments = remove_redundant(&segments);
+
+    let mut writer = Writer {
+        output: String::new(),
+        last_letter: None,
+        last_number_has_dot: false,
+        precision,
+    };
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    let mut previous = None;
+
+    for segment in &segments {
+        let relative = |(x, y): Point| (x - current.0, y - current.1);
+        let (letter, absolute_values, relative_values): (u8, Vec<f64>, Vec<f64>) = match *segment {
+            Segment::MoveTo(to) => {
+                start = to;
+                (b'M', vec![to.0, to.1], point_args(&[relative(to)]))
+            }
+            Segment::LineTo(to) if to.1 == current.1 => (b'H', vec![to.0], vec![to.0 - current.0]),
+            Segment::LineTo(to) if to.0 == current.0 => (b'V', vec![to.1], vec![to.1 - current.1]),
+            Segment::LineTo(to) => (b'L', point_args(&[to]), point_args(&[relative(to)])),
+            Segment::CurveTo(c1, c2, to)
+                if round_point(reflect(previous.as_ref(), current, true), precision) == c1 =>
+            {
+                (
+                    b'S',
+                    point_args(&[c2, to]),
+                    point_args(&[relative(c2), relative(to)]),
+                )
+            }
+            Segment::CurveTo(c1, c2, to) => (
+                b'C',
+                point_args(&[c1, c2, to]),
+                point_args(&[relative(c1), relative(c2), relative(to)]),
+            ),
+            Segment::QuadTo(c, to)
+                if round_point(reflect(previous.as_ref(), current, false), precision) == c =>
+            {
+                (b'T', point_args(&[to]), point_args(&[relative(to)]))
+            }
+            Segment::QuadTo(c, to) => (
+                b'Q',
+                point_args(&[c, to]),
+                point_args(&[relative(c), relative(to)]),
+            ),
+            Segment::Arc {
+                radii,
+                rotation,
+                large_arc,
+                sweep,
+                to,
+            } => {
+                let flags = [radii.0, radii.1, rotation, flag(large_arc), flag(sweep)];
+                let relative_to = relative(to);
+                (
+                    b'A',
+                    [&flags[..], &[to.0, to.1]].concat(),
+                    [&flags[..], &[relative_to.0, relative_to.1]].concat(),
+                )
+            }
+            Segment::Close => (b'Z', vec![], vec![]),
+        };
+
+        if letter == b'Z' {
+            writer.write(b'z', &[]);
+        } else {
+            // Relative values are differences of rounded numbers, so rounding
+            // them again only removes floating point noise: no error builds up.
+            let relative_values = relative_values
+                .iter()
+                .map(|value| round(*value, precision))
+                .collect::<Vec<_>>();
+            // Ties go to the relative form, which compresses better, except
+            // for the first moveto.
+            let relative_letter = letter.to_ascii_lowercase();
+            if !writer.output.is_empty()
+                && writer.format(relative_letter, &relative_values).len()
+                    <= writer.format(letter, &absolute_values).len()
+            {
+                writer.write(relative_letter, &relative_values);
+            } else {
+                writer.write(letter, &absolute_values);
+            }
+        }
+
+        current = segment.end().unwrap_or(start);
+        previous = Some(*segment);
+    }
+
+    writer.output
+}
+
+/// A box containing every point the path goes through. It is built from the
+/// control points, so it may be larger than the path.
+pub(crate) fn bounding_box(segments: &[Segment]) -> Option<Bounds> {
+    let mut points = vec![];
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    for segment in segments {
+        match *segment {
+            Segment::MoveTo(to) => {
+                start = to;
+                points.push(to);
+            }
+            Segment::LineTo(to) => points.push(to),
+            Segment::CurveTo(c1, c2, to) => points.extend([c1, c2, to]),
+            Segment::QuadTo(c, to) => points.extend([c, to]),
+            Segment::Arc { radii, to, .. } => {
+                // Radii too small to join the ends are scaled up until the arc
+                // is a half ellipse, whose points are within the diameter of
+                // either end.
+                let chord = ((to.0 - current.0).powi(2) + (to.1 - current.1).powi(2)).sqrt();
+                let reach = 2.0 * radii.0.max(radii.1).max(chord / 2.0);
+                points.extend([
+                    (current.0 - reach, current.1 - reach),
+                    (current.0 + reach, current.1 + reach),
+                    to,
+                ]);
+            }
+            Segment::Close => {}
+        }
+        current = segment.end().unwrap_or(start);
+    }
+
+    points
+        .into_iter()
+        .fold(None, |bounds, (x, y)| match bounds {
+            None => Some(((x, y), (x, y))),
+            Some(((min_x, min_y), (max_x, max_y))) => {
+                Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+            }
+        })
+}
+
+/// Drops the segments that do not change the rendering: zero-length segments
+/// in subpaths drawing something else (a lone one is a dot with round caps),
+/// lines back to the start of a subpath right before it is closed, repeated
+/// closes, and subpaths made of a single moveto.
+fn remove_redundant(segments: &[Segment]) -> Vec<Segment> {
+    let mut subpaths = vec![];
+    let mut begin = 0;
+    for (index, segment) in segments.iter().enumerate().skip(1) {
+        if let Segment::MoveTo(_) = segment {
+            subpaths.push(&segments[begin..index]);
+            begin = index;
+        }
+    }
+    subpaths.push(&segments[begin..]);
+
+    if subpaths.iter().all(|subpath| subpath.len() == 1) {
+        return segments.to_vec();
+    }
+
+    let mut result = vec![];
+    for subpath in subpaths.into_iter().filter(|subpath| subpath.len() > 1) {
+        let start = subpath[0].end().unwrap_or_default();
+        let mut current = start;
+        let draws = subpath[1..].iter().any(|segment| {
+            let from = current;
+            current = segment.end().unwrap_or(start);
+            *segment != Segment::Close && !segment.is_zero_length(from)
+        });
+
+        current = start;
+        result.push(subpath[0]);
+        for (index, segment) in subpath.iter().enumerate().skip(1) {
+            let from = current;
+            current = segment.end().unwrap_or(start);
+            let redundant = match *segment {
+                Segment::Close => result.last() == Some(&Segment::Close),
+                Segment::LineTo(to)
+                    if to == start && subpath.get(index + 1) == Some(&Segment::Close) =>
+                {
+                    true
+                }
+                // Arcs ending where they start are not rendered at all.
+                Segment::Arc { to, .. } => to == from,
+                _ => draws && segment.is_zero_length(from),
+            };
+            if !redundant {
+                result.push(*segment);
+            }
+        }
+    }
+
+    result
+}
+
+/// The first control point of an `S` (`cubic`) or `T` command following
+/// `previous`.
+fn reflect(previous: Option<&Segment>, current: Point, cubic: bool) -> Point {
+    match (previous, cubic) {
+        (Some(Segment::CurveTo(_, control, _)), true)
+        | (Some(Segment::QuadTo(control, _)), false) => {
+            (2.0 * current.0 - control.0, 2.0 * current.1 - control.1)
+        }
+        _ => current,
+    }
+}
+
+struct Writer {
+    output: String,
+    /// The command repeated by numbers written next. After a moveto, it is a
+    /// lineto.
+    last_letter: Option<u8>,
+    last_number_has_dot: bool,
+    precision: u32,
+}
+
+impl Writer {
+    /// The text `write` appends for the command.
+    fn format(&self, letter: u8, values: &[f64]) -> String {
+        let mut text = String::new();
+        let mut after_letter = false;
+        if self.last_letter != Some(letter) || matches!(letter, b'M' | b'm' | b'z') {
+            text.push(letter as char);
+            after_letter = true;
+        }
+
+        let mut has_dot = self.last_number_has_dot;
+        for value in values {
+            let number = format_number(*value, self.precision);
+            // A sign or a second dot starts a new number on its own.
+            if !(after_letter || number.starts_with('-') || (has_dot && number.starts_with('.'))) {
+                text.push(' ');
+            }
+            after_letter = false;
+            has_dot = number.contains('.');
+            text.push_str(&number);
+        }
+
+        text
+    }
+
+    fn write(&mut self, letter: u8, values: &[f64]) {
+        let text = self.format(letter, values);
+        self.output.push_str(&text);
+        self.last_letter = Some(match letter {
+            b'M' => b'L',
+            b'm' => b'l',
+            _ => letter,
+        });
+        self.last_number_has_dot = values
+            .last()
+            .is_some_and(|value| format_number(*value, self.precision).contains('.'));
+    }
+}
+
+/// Formats `value` without trailing zeros nor a leading zero: `-0.50` is `-.5`.
+pub(crate) fn format_number(value: f64, precision: u32) -> String {
+    let mut number = format!("{:.*}", precision as usize, round(value, precision));
+    if number.contains('.') {
+        number.truncate(number.trim_end_matches('0').trim_end_matches('.').len());
+    }
+
+    if number == "-0" {
+        "0".to_string()
+    } else if let Some(fraction) = number.strip_prefix("0.") {
+        format!(".{}", fraction)
+    } else if let Some(fraction) = number.strip_prefix("-0.") {
+        format!("-.{}", fraction)
+    } else {
+        number
+    }
+}
+
+/// The most decimals a `precision` option keeps: `f64` holds no more
+/// significant digits, and larger factors overflow in `round`.
+pub(crate) const MAX_PRECISION: u32 = 15;
+
+/// `precision` clamped to `MAX_PRECISION`, with the error to report when it
+/// was larger.
+pub(crate) fn clamp_precision(precision: u32) -> (u32, Option<ConfigError>) {
+    if precision <= MAX_PRECISION {
+        return (precision, None);
+    }
+    let err = ConfigError::new("precision", "precision is too large")
+        .with_value(precision.to_string())
+        .with_hint(format!(
+            "keep at most {} decimals; falling back to {}",
+            MAX_PRECISION, MAX_PRECISION
+        ));
+    (MAX_PRECISION, Some(err))
+}
+
+pub(crate) fn round(value: f64, precision: u32) -> f64 {
+    let factor = 10f64.powi(precision as i32);
+    (value * factor).round() / factor
+}
+
+fn round_point((x, y): Point, precision: u32) -> Point {
+    (round(x, precision), round(y, precision))
+}
+
+fn point_args(points: &[Point]) -> Vec<f64> {
+    points.iter().flat_map(|(x, y)| [*x, *y]).collect()
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(
+            self.peek(),
+            Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b',')
+        ) {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.bytes.len()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let letter = self
+            .peek()
+            .filter(|letter| b"MmZzLlHhVvCcSsQqTtAa".contains(letter))?;
+        self.pos += 1;
+        Some(letter)
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f64, ()> {
+        self.skip_separators();
+        let start = self.pos;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.digits();
+        }
+        if digits == 0 {
+            return Err(());
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or(())
+    }
+
+    /// Arc flags are a single digit, so `a1 1 0 00 1 1` is valid.
+    fn flag(&mut self) -> Result<bool, ()> {
+        self.skip_separators();
+        let value = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(()),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minify(d: &str) -> String {
+        minify_path(&parse_path(d).unwrap(), 3)
+    }
+
+    #[test]
+    fn parse_path_data() {
+        assert_eq!(
+            parse_path("m1,1 h2 v-1.5e1 s1-1 2 2 t.5.5 a1 1 0 014 4z").unwrap(),
+            [
+                Segment::MoveTo((1.0, 1.0)),
+                Segment::LineTo((3.0, 1.0)),
+                Segment::LineTo((3.0, -14.0)),
+                Segment::CurveTo((3.0, -14.0), (4.0, -15.0), (5.0, -12.0)),
+                Segment::QuadTo((5.0, -12.0), (5.5, -11.5)),
+                Segment::Arc {
+                    radii: (1.0, 1.0),
+                    rotation: 0.0,
+                    large_arc: false,
+                    sweep: true,
+                    to: (9.5, -7.5),
+                },
+                Segment::Close,
+            ]
+        );
+        assert!(parse_path("L1 1").is_err());
+        assert!(parse_path("M1 1 L2").is_err());
+        assert!(parse_path("M1 1 Z 2 2").is_err());
+    }
+
+    #[test]
+    fn pick_shortest_commands() {
+        assert_eq!(
+            minify("M 10.000 20.000 L 10 30 L 25 30 L 10 20 Z"),
+            "M10 20v10h15z"
+        );
+        assert_eq!(
+            minify("M100 100 L 200 200 L 100 100 L 200 200"),
+            "M100 100l100 100-100-100 100 100"
+        );
+        assert_eq!(
+            minify("M0 0 C 0 0 1 1 2 2 C 3 3 4 4 5 5"),
+            "M0 0s1 1 2 2 2 2 3 3"
+        );
+    }
+
+    #[test]
+    fn round_numbers() {
+        assert_eq!(minify("M0.12345 -0.5L1.0001 1.4999"), "M.123-.5 1 1.5");
+        // Relative values are taken between rounded points.
+        assert_eq!(
+            minify_path(&parse_path("M0.4 0.4 L1.4 0.4 L2.4 0.4").unwrap(), 0),
+            "M0 0h1 1"
+        );
+    }
+
+    #[test]
+    fn remove_redundant_segments() {
+        assert_eq!(minify("M5 5 L5 5 L10 10 L5 5 Z Z"), "M5 5l5 5z");
+        assert_eq!(minify("M1 1 M5 5 L10 10"), "M5 5l5 5");
+        // A lone zero-length segment renders a dot with round caps.
+        assert_eq!(minify("M5 5 L5 5"), "M5 5h0");
+    }
+}
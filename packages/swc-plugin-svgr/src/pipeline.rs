@@ -0,0 +1,107 @@
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::error::ConfigError;
+
+/// A pass that transforms one JSX element at a time, so it can share a single
+/// traversal with the other passes instead of walking the program on its own.
+pub trait ElementPass {
+    /// Called before the children of `jsx_element` are visited.
+    fn enter_element(&mut self, _jsx_element: &mut JSXElement) {}
+
+    /// Called once every child of `jsx_element` went through all the passes.
+    fn exit_element(&mut self, jsx_element: &mut JSXElement);
+
+    /// Called once the traversal running this pass went through the whole
+    /// program, for changes outside of the JSX tree such as imports.
+    fn finish(&mut self, _program: &mut Program) {}
+
+    /// Config problems found so far, keyed by their path in the pass options.
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        vec![]
+    }
+}
+
+/// Runs element passes in a single traversal. For every element, the passes
+/// run in the order they were pushed, once all of its children went through
+/// every pass, including the passes pushed after them. A pass that must see
+/// the tree as the earlier passes alone leave it, e.g. because it walks it
+/// top-down, defers its work to an enclosing element or to `finish`. The
+/// registry tests check that the built-in passes give the same result as
+/// running each of them over the whole program one after another.
+#[derive(Default)]
+pub struct ElementPipeline {
+    passes: Vec<(&'static str, Box<dyn ElementPass>)>,
+}
+
+impl ElementPipeline {
+    /// Adds a pass; `section` is the `Options` key its errors are nested under.
+    pub fn push(&mut self, section: &'static str, pass: Box<dyn ElementPass>) {
+        self.passes.push((section, pass));
+    }
+
+    /// Runs every pass over `program`, then lets each of them finish.
+    pub fn run(&mut self, program: &mut Program) {
+        program.visit_mut_with(self);
+        for (_, pass) in self.passes.iter_mut() {
+            pass.finish(program);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    pub fn take_errors(&mut self) -> Vec<ConfigError> {
+        self.passes
+            .iter_mut()
+            .flat_map(|(section, pass)| {
+                pass.take_errors()
+                    .into_iter()
+                    .map(|err| err.nested(section))
+            })
+            .collect()
+    }
+}
+
+impl VisitMut for ElementPipeline {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        for (_, pass) in self.passes.iter_mut() {
+            pass.enter_element(jsx_element);
+        }
+
+        jsx_element.visit_mut_children_with(self);
+
+        for (_, pass) in self.passes.iter_mut() {
+            pass.exit_element(jsx_element);
+        }
+    }
+}
+
+/// Whether `jsx_element` is named after one of `elements`.
+pub fn is_element_of(jsx_element: &JSXElement, elements: &[String]) -> bool {
+    match &jsx_element.opening.name {
+        JSXElementName::Ident(Ident { sym, .. }) => elements.iter().any(|element| *element == **sym),
+        _ => false,
+    }
+}
+
+/// Lowercases `name` and drops its separators: `clip-path`, `clipPath` and
+/// `clip:path` all give `clippath`. Passes changing the children of an element
+/// see them once every pass went through them, so they compare names whether
+/// they were transformed or not.
+pub(crate) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// The normalized name of `jsx_element`, e.g. `g` for both `<g>` and `<G>`.
+pub(crate) fn element_name(jsx_element: &JSXElement) -> Option<String> {
+    match &jsx_element.opening.name {
+        JSXElementName::Ident(Ident { sym, .. }) => Some(normalize(sym)),
+        _ => None,
+    }
+}
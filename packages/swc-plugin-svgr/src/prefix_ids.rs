@@ -0,0 +1,255 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, iter, ops::Range};
+use swc_core::ecma::{
+    ast::*,
+    visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use transform_svg_component::references::{reference_spans, replace_spans, style_reference_spans};
+
+use crate::{
+    pipeline::ElementPass,
+    references::{attribute_name, element_id, is_style},
+};
+
+fn default_prefix() -> String {
+    "{componentName}-".to_string()
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Options {
+    /// Prepended to every id; `{componentName}` is replaced with the name of
+    /// the component.
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            prefix: default_prefix(),
+        }
+    }
+}
+
+/// Prefixes the ids defined in the document, and every reference to them, so
+/// that the gradients, clip paths and masks of several icons on one page do
+/// not collide.
+///
+/// References are `url(#id)` values, `href` and `xlink:href` attributes,
+/// the id lists of `aria-labelledby` and similar attributes, SMIL `begin` and
+/// `end` timing, inline styles and `<style>` contents. References to ids the
+/// document does not define are left as is.
+///
+/// In a pipeline, ids are prefixed once every element went through the other
+/// passes, as the passes removing unused or hidden elements look ids up
+/// unprefixed when exiting the elements holding them.
+pub struct PrefixIdsVisitor {
+    prefix: String,
+    /// Collected when the pass starts prefixing.
+    ids: Option<HashSet<String>>,
+}
+
+impl PrefixIdsVisitor {
+    pub fn new(options: Options, component_name: &str) -> Self {
+        PrefixIdsVisitor {
+            prefix: options.prefix.replace("{componentName}", component_name),
+            ids: None,
+        }
+    }
+
+    /// `value` with the ids at `spans` prefixed, when the document defines
+    /// at least one of them.
+    fn prefix_spans(
+        &self,
+        value: &str,
+        spans: impl IntoIterator<Item = Range<usize>>,
+    ) -> Option<String> {
+        let ids = self.ids.as_ref()?;
+        let spans = spans
+            .into_iter()
+            .filter(|span| ids.contains(&value[span.clone()]))
+            .collect::<Vec<_>>();
+        if spans.is_empty() {
+            return None;
+        }
+        Some(replace_spans(value, &spans, |id| {
+            format!("{}{}", self.prefix, id)
+        }))
+    }
+
+    fn prefix_attribute(&self, name: &str, value: &str) -> Option<String> {
+        if name == "id" {
+            self.prefix_spans(value, iter::once(0..value.len()))
+        } else {
+            self.prefix_spans(value, reference_spans(name, value))
+        }
+    }
+
+    fn prefix_style(&self, child: &mut JSXElementChild) {
+        match child {
+            JSXElementChild::JSXText(text) => {
+                if let Some(css) =
+                    self.prefix_spans(&text.value, style_reference_spans(&text.value))
+                {
+                    text.value = css.clone().into();
+                    text.raw = css.into();
+                }
+            }
+            JSXElementChild::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            }) => match &mut **expr {
+                Expr::Lit(Lit::Str(str)) => {
+                    if let Some(css) =
+                        self.prefix_spans(&str.value, style_reference_spans(&str.value))
+                    {
+                        str.value = css.into();
+                        str.raw = None;
+                    }
+                }
+                Expr::Tpl(Tpl { quasis, .. }) => {
+                    for quasi in quasis {
+                        if let Some(css) =
+                            self.prefix_spans(&quasi.raw, style_reference_spans(&quasi.raw))
+                        {
+                            quasi.cooked = Some(css.clone().into());
+                            quasi.raw = css.into();
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+impl ElementPass for PrefixIdsVisitor {
+    fn exit_element(&mut self, _jsx_element: &mut JSXElement) {}
+
+    fn finish(&mut self, program: &mut Program) {
+        let mut ids = IdCollector::default();
+        program.visit_with(&mut ids);
+        self.ids = Some(ids.0);
+        program.visit_mut_children_with(self);
+    }
+}
+
+impl VisitMut for PrefixIdsVisitor {
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        self.finish(program);
+    }
+
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name, value, .. }) = attr else {
+                continue;
+            };
+            let name = attribute_name(name);
+            match value {
+                Some(JSXAttrValue::Lit(Lit::Str(str))) => {
+                    if let Some(value) = self.prefix_attribute(&name, &str.value) {
+                        str.value = value.into();
+                        str.raw = None;
+                    }
+                }
+                // Style objects, once `transform_attribute` ran.
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) if name == "style" => {
+                    let Expr::Object(ObjectLit { props, .. }) = &mut **expr else {
+                        continue;
+                    };
+                    for prop in props.iter_mut() {
+                        let PropOrSpread::Prop(prop) = prop else {
+                            continue;
+                        };
+                        let Prop::KeyValue(KeyValueProp { value, .. }) = &mut **prop else {
+                            continue;
+                        };
+                        let Expr::Lit(Lit::Str(str)) = &mut **value else {
+                            continue;
+                        };
+                        if let Some(value) = self.prefix_attribute(&name, &str.value) {
+                            str.value = value.into();
+                            str.raw = None;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if is_style(jsx_element) {
+            for child in jsx_element.children.iter_mut() {
+                self.prefix_style(child);
+            }
+        }
+    }
+}
+
+/// The ids defined in the document.
+#[derive(Default)]
+struct IdCollector(HashSet<String>);
+
+impl Visit for IdCollector {
+    fn visit_jsx_element(&mut self, jsx_element: &JSXElement) {
+        if let Some(id) = element_id(jsx_element) {
+            self.0.insert(id.to_string());
+        }
+        jsx_element.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(PrefixIdsVisitor::new(Options::default(), "SvgStar")),
+        prefix_ids,
+        r##"<svg aria-labelledby="title external"><title id="title">Star</title><style>{".a{fill:url(#g)} #p{stroke:#fff}"}</style><defs><linearGradient id="g" /><path id="p" d="M0 0h1" /></defs><use xlinkHref="#p" href="#other" fill="url('#g')" style="stroke: url(#g)" /><animate id="anim" begin="0s; anim.end+1s" /></svg>;"##,
+        r##"<svg aria-labelledby="SvgStar-title external"><title id="SvgStar-title">Star</title><style>{".a{fill:url(#SvgStar-g)} #SvgStar-p{stroke:#fff}"}</style><defs><linearGradient id="SvgStar-g" /><path id="SvgStar-p" d="M0 0h1" /></defs><use xlinkHref="#SvgStar-p" href="#other" fill="url('#SvgStar-g')" style="stroke: url(#SvgStar-g)" /><animate id="SvgStar-anim" begin="0s; SvgStar-anim.end+1s" /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(PrefixIdsVisitor::new(
+            Options {
+                prefix: "icons__{componentName}__".to_string()
+            },
+            "Logo"
+        )),
+        prefix_ids_with_template,
+        r##"<svg><clipPath id="a" /><g clipPath="url(#a)" mask="url(#b)" style={{ clipPath: "url(#a)" }} /><title id={titleId}>{title}</title></svg>;"##,
+        r##"<svg><clipPath id="icons__Logo__a" /><g clipPath="url(#icons__Logo__a)" mask="url(#b)" style={{ clipPath: "url(#icons__Logo__a)" }} /><title id={titleId}>{title}</title></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(PrefixIdsVisitor::new(Options::default(), "SvgIcon")),
+        keep_aria_label_text,
+        r##"<svg aria-label="Shape" aria-describedby="Shape"><path id="Shape" aria-roledescription="Shape" d="M0 0h1" /></svg>;"##,
+        r##"<svg aria-label="Shape" aria-describedby="SvgIcon-Shape"><path id="SvgIcon-Shape" aria-roledescription="Shape" d="M0 0h1" /></svg>;"##
+    );
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use swc_core::ecma::ast::*;
+use transform_svg_component::references::{reference_spans, style_reference_spans};
+
+use crate::pipeline::{element_name, normalize};
+
+/// Collects the ids referenced from `jsx_element` and its descendants, by
+/// `url(#id)` values, `href` or `xlink:href` attributes, the id lists of
+/// `aria-labelledby` and similar attributes, SMIL timing and `<style>`
+/// contents.
+pub(crate) fn collect_references(jsx_element: &JSXElement, references: &mut HashSet<String>) {
+    for attr in &jsx_element.opening.attrs {
+        if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name,
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+            ..
+        }) = attr
+        {
+            for span in reference_spans(&attribute_name(name), value) {
+                references.insert(value[span].to_string());
+            }
+        }
+    }
+
+    let is_style = is_style(jsx_element);
+    for child in &jsx_element.children {
+        match child {
+            JSXElementChild::JSXElement(child) => collect_references(child, references),
+            _ if is_style => {
+                for css in style_texts(child) {
+                    for span in style_reference_spans(css) {
+                        references.insert(css[span].to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The name-agnostic form of an attribute name, see `pipeline::normalize`.
+pub(crate) fn attribute_name(name: &JSXAttrName) -> String {
+    match name {
+        JSXAttrName::Ident(Ident { sym, .. }) => normalize(sym),
+        JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+            normalize(&format!("{}{}", ns.sym, name.sym))
+        }
+    }
+}
+
+pub(crate) fn is_style(jsx_element: &JSXElement) -> bool {
+    element_name(jsx_element).as_deref() == Some("style")
+}
+
+/// The text of a `<style>` child: JSX text, or the string or template literal
+/// `parse_svg` wraps style sheets in.
+pub(crate) fn style_texts(child: &JSXElementChild) -> Vec<&str> {
+    match child {
+        JSXElementChild::JSXText(JSXText { value, .. }) => vec![value],
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        }) => match &**expr {
+            Expr::Lit(Lit::Str(Str { value, .. })) => vec![value],
+            Expr::Tpl(Tpl { quasis, .. }) => quasis.iter().map(|quasi| &*quasi.raw).collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// The value of the `id` attribute of `jsx_element`.
+pub(crate) fn element_id(jsx_element: &JSXElement) -> Option<&str> {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+                ..
+            }) if sym == "id" => Some(&**value),
+            _ => None,
+        })
+}
+
+/// Whether `jsx_element` has an `id` that is not a string, such as the
+/// `useId` based ids of `transform_svg_component`, which may be referenced in
+/// ways that cannot be told statically.
+pub(crate) fn has_expression_id(jsx_element: &JSXElement) -> bool {
+    jsx_element.opening.attrs.iter().any(|attr| {
+        matches!(
+            attr,
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(JSXAttrValue::JSXExprContainer(_)),
+                ..
+            }) if sym == "id"
+        )
+    })
+}
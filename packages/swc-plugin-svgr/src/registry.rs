@@ -0,0 +1,1132 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use swc_core::{
+    ecma::{ast::*, visit::VisitMutWith},
+    plugin::{
+        metadata::TransformPluginMetadataContextKind,
+        proxies::{PluginCommentsProxy, TransformPluginProgramMetadata},
+    },
+};
+use transform_svg_component::{
+    component_name::DEFAULT_COMPONENT_NAME,
+    config::Config as TransformSVGComponentOptions,
+    error::{parse_config, ConfigError},
+    TransformSVGComponentVisitor,
+};
+
+use crate::{
+    add_jsx_attribute::AddJSXAttributeVisitor,
+    cleanup::CleanupVisitor,
+    cleanup_numeric_values::CleanupNumericValuesVisitor,
+    collapse_groups::CollapseGroupsVisitor,
+    convert_shapes::ConvertShapesVisitor,
+    css_variables::CssVariablesVisitor,
+    current_color::CurrentColorVisitor,
+    inline_styles::InlineStylesVisitor,
+    minify_path_data::MinifyPathDataVisitor,
+    parse_options,
+    pipeline::{ElementPass, ElementPipeline},
+    prefix_ids::PrefixIdsVisitor,
+    remove_hidden_elements::RemoveHiddenElementsVisitor,
+    remove_jsx_attribute::RemoveJSXAttributeVisitor,
+    remove_jsx_empty_expression::RemoveEmptyExpressionVisitor,
+    remove_unused_ids::RemoveUnusedIdsVisitor,
+    replace_jsx_attribute_value::ReplaceJSXAttributeValueVisitor,
+    svg_dynamic_title::{DynamicTitleVisitor, Options as DynamicTitleOptions},
+    svg_em_dimensions::SVGEmDimensionsVisitor,
+    transform_attribute::TransformAttributeVisitor,
+    transform_react_native_svg::TransformReactNativeSVGVisitor,
+    Options,
+};
+
+/// A named transform step that can be registered in a [`PassRegistry`].
+///
+/// Downstream crates implement it to add their own passes, then build their
+/// own plugin with [`PassRegistry::process_transform`].
+pub trait SvgrPass {
+    /// Unique name, used in ordering constraints and as the config section
+    /// the errors of the pass are reported under.
+    fn name(&self) -> &'static str;
+
+    /// Passes that must run before this one.
+    fn after(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Passes that must run after this one.
+    fn before(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Creates the pass for one run, or `None` when the options disable it.
+    fn create(&self, context: &PassContext) -> Result<Option<Pass>, ConfigError>;
+}
+
+/// A pass created for one run.
+pub enum Pass {
+    /// Visits the whole program on its own.
+    Program(Box<dyn ProgramPass>),
+    /// Shares a single traversal with the element passes next to it.
+    Element(Box<dyn ElementPass>),
+}
+
+pub trait ProgramPass {
+    fn run(&mut self, program: &mut Program);
+
+    /// Config problems found while running, keyed by their path in the pass
+    /// options.
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        vec![]
+    }
+}
+
+impl ProgramPass for TransformSVGComponentVisitor {
+    fn run(&mut self, program: &mut Program) {
+        program.visit_mut_with(self);
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+/// What passes are created from.
+pub struct PassContext<'a> {
+    pub options: &'a Options,
+    pub comments: Option<PluginCommentsProxy>,
+}
+
+impl PassContext<'_> {
+    /// Deserializes the config section of a custom pass, i.e. the `Options`
+    /// key named after it. Gives `None` when the key is missing or `null`.
+    pub fn section<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, ConfigError> {
+        match self.options.custom.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(section) => parse_config(&section.to_string())
+                .map(Some)
+                .map_err(|err| err.nested(name)),
+        }
+    }
+}
+
+/// A pass registration that cannot be satisfied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// Two passes share a name.
+    DuplicatePass(String),
+    /// A pass is constrained against a pass that was never registered.
+    UnknownPass { pass: String, constraint: String },
+    /// The ordering constraints of these passes form a cycle.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::DuplicatePass(name) => {
+                write!(f, "a pass named `{}` is already registered", name)
+            }
+            RegistryError::UnknownPass { pass, constraint } => write!(
+                f,
+                "pass `{}` is ordered against `{}`, which is not registered",
+                pass, constraint
+            ),
+            RegistryError::Cycle(passes) => write!(
+                f,
+                "ordering constraints form a cycle through {}",
+                passes
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<RegistryError> for ConfigError {
+    fn from(err: RegistryError) -> Self {
+        ConfigError::new("", err.to_string())
+            .with_hint("fix the `SvgrPass` implementations registered in the plugin")
+    }
+}
+
+/// The passes of a plugin. Passes run in registration order, except where
+/// their `after` and `before` constraints require otherwise.
+#[derive(Default)]
+pub struct PassRegistry {
+    passes: Vec<Box<dyn SvgrPass>>,
+}
+
+impl PassRegistry {
+    /// A registry with the passes of `swc-plugin-svgr`, in their default order.
+    pub fn with_builtin_passes() -> Self {
+        let mut registry = PassRegistry::default();
+        for pass in BUILTIN_PASSES {
+            registry.passes.push(Box::new(pass));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, pass: impl SvgrPass + 'static) -> Result<(), RegistryError> {
+        if self
+            .passes
+            .iter()
+            .any(|registered| registered.name() == pass.name())
+        {
+            return Err(RegistryError::DuplicatePass(pass.name().to_string()));
+        }
+        self.passes.push(Box::new(pass));
+        Ok(())
+    }
+
+    /// Sorts the passes so that every constraint holds, keeping registration
+    /// order between unconstrained passes.
+    pub fn order(&self) -> Result<Vec<&dyn SvgrPass>, RegistryError> {
+        let index_of = |pass: &dyn SvgrPass, name: &str| {
+            self.passes
+                .iter()
+                .position(|registered| registered.name() == name)
+                .ok_or_else(|| RegistryError::UnknownPass {
+                    pass: pass.name().to_string(),
+                    constraint: name.to_string(),
+                })
+        };
+
+        // `predecessors[i]` lists the passes that must run before pass `i`.
+        let mut predecessors = vec![vec![]; self.passes.len()];
+        for (index, pass) in self.passes.iter().enumerate() {
+            for name in pass.after() {
+                predecessors[index].push(index_of(pass.as_ref(), name)?);
+            }
+            for name in pass.before() {
+                predecessors[index_of(pass.as_ref(), name)?].push(index);
+            }
+        }
+
+        // Depth first, in registration order: a pass pulls the passes it
+        // depends on forward instead of being pushed back behind the others.
+        let mut ordered: Vec<usize> = Vec::with_capacity(self.passes.len());
+        let mut stack: Vec<usize> = vec![];
+        for index in 0..self.passes.len() {
+            self.visit(index, &predecessors, &mut stack, &mut ordered)?;
+        }
+
+        Ok(ordered
+            .into_iter()
+            .map(|index| self.passes[index].as_ref())
+            .collect())
+    }
+
+    /// Picks the passes named in the `pipeline` option, in that order. Passes
+    /// it leaves out do not run, and the ordering constraints must hold
+    /// between the passes it lists.
+    pub fn order_by(&self, names: &[String]) -> Result<Vec<&dyn SvgrPass>, Vec<ConfigError>> {
+        let mut errors: Vec<ConfigError> = vec![];
+        let mut passes: Vec<&dyn SvgrPass> = vec![];
+
+        for (index, name) in names.iter().enumerate() {
+            let path = format!("pipeline[{}]", index);
+            match self.passes.iter().find(|pass| pass.name() == name) {
+                _ if names[..index].contains(name) => errors.push(
+                    ConfigError::new(path, "pass is listed more than once")
+                        .with_value(format!("\"{}\"", name)),
+                ),
+                Some(pass) => passes.push(pass.as_ref()),
+                None => errors.push(
+                    ConfigError::new(path, "unknown pass")
+                        .with_value(format!("\"{}\"", name))
+                        .with_hint(format!(
+                            "expected one of {}",
+                            self.passes
+                                .iter()
+                                .map(|pass| format!("`{}`", pass.name()))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )),
+                ),
+            }
+        }
+
+        let position = |name: &str| passes.iter().position(|pass| pass.name() == name);
+        for (index, pass) in passes.iter().enumerate() {
+            let path = format!(
+                "pipeline[{}]",
+                names
+                    .iter()
+                    .position(|name| name == pass.name())
+                    .unwrap_or(index)
+            );
+            for after in pass.after() {
+                if position(after).is_some_and(|position| position > index) {
+                    errors.push(
+                        ConfigError::new(
+                            path.clone(),
+                            format!("`{}` must run after `{}`", pass.name(), after),
+                        )
+                        .with_hint(format!(
+                            "move `{}` after `{}` in `pipeline`",
+                            pass.name(),
+                            after
+                        )),
+                    );
+                }
+            }
+            for before in pass.before() {
+                if position(before).is_some_and(|position| position < index) {
+                    errors.push(
+                        ConfigError::new(
+                            path.clone(),
+                            format!("`{}` must run before `{}`", pass.name(), before),
+                        )
+                        .with_hint(format!(
+                            "move `{}` before `{}` in `pipeline`",
+                            pass.name(),
+                            before
+                        )),
+                    );
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(passes)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn visit(
+        &self,
+        index: usize,
+        predecessors: &[Vec<usize>],
+        stack: &mut Vec<usize>,
+        ordered: &mut Vec<usize>,
+    ) -> Result<(), RegistryError> {
+        if ordered.contains(&index) {
+            return Ok(());
+        }
+        if let Some(start) = stack.iter().position(|visiting| *visiting == index) {
+            return Err(RegistryError::Cycle(
+                stack[start..]
+                    .iter()
+                    .map(|index| self.passes[*index].name().to_string())
+                    .collect(),
+            ));
+        }
+
+        stack.push(index);
+        let mut before = predecessors[index].clone();
+        before.sort_unstable();
+        for predecessor in before {
+            self.visit(predecessor, predecessors, stack, ordered)?;
+        }
+        stack.pop();
+        ordered.push(index);
+        Ok(())
+    }
+
+    /// Runs every enabled pass on `program` and returns the config errors the
+    /// passes recovered from, with their key paths relative to `Options`.
+    /// Consecutive element passes share a single traversal.
+    pub fn transform(
+        &self,
+        mut program: Program,
+        options: &Options,
+        comments: Option<PluginCommentsProxy>,
+    ) -> (Program, Vec<ConfigError>) {
+        let passes = match &options.pipeline {
+            Some(names) => match self.order_by(names) {
+                Ok(passes) => passes,
+                Err(errors) => return (program, errors),
+            },
+            None => match self.order() {
+                Ok(passes) => passes,
+                Err(err) => return (program, vec![err.into()]),
+            },
+        };
+
+        let context = PassContext { options, comments };
+        let mut errors: Vec<ConfigError> = vec![];
+        let mut pipeline = ElementPipeline::default();
+
+        for pass in passes {
+            match pass.create(&context) {
+                Ok(Some(Pass::Element(element_pass))) => pipeline.push(pass.name(), element_pass),
+                Ok(Some(Pass::Program(mut program_pass))) => {
+                    run_pipeline(&mut pipeline, &mut program, &mut errors);
+                    program_pass.run(&mut program);
+                    errors.extend(
+                        program_pass
+                            .take_errors()
+                            .into_iter()
+                            .map(|err| err.nested(pass.name())),
+                    );
+                }
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        run_pipeline(&mut pipeline, &mut program, &mut errors);
+
+        (program, errors)
+    }
+
+    /// The body of a `#[plugin_transform]` function running these passes.
+    pub fn process_transform(
+        &self,
+        program: Program,
+        metadata: TransformPluginProgramMetadata,
+    ) -> Program {
+        let mut options = match metadata.get_transform_plugin_config() {
+            Some(config) => match parse_options(&config) {
+                Ok(options) => options,
+                Err(err) => {
+                    err.emit();
+                    return program;
+                }
+            },
+            None => {
+                ConfigError::new("", "failed to get plugin config for swc-plugin-svgr")
+                    .with_hint(
+                        "pass an options object to swc-plugin-svgr in `jsc.experimental.plugins`",
+                    )
+                    .emit();
+                return program;
+            }
+        };
+
+        if let Some(config) = options.transform_svg_component.as_mut() {
+            config.resolve_component_name(
+                metadata
+                    .get_context(&TransformPluginMetadataContextKind::Filename)
+                    .as_deref(),
+            );
+        }
+
+        let (program, errors) = self.transform(program, &options, metadata.comments);
+        for err in errors {
+            err.emit();
+        }
+
+        program
+    }
+}
+
+fn run_pipeline(
+    pipeline: &mut ElementPipeline,
+    program: &mut Program,
+    errors: &mut Vec<ConfigError>,
+) {
+    if !pipeline.is_empty() {
+        let mut pipeline = std::mem::take(pipeline);
+        pipeline.run(program);
+        errors.extend(pipeline.take_errors());
+    }
+}
+
+/// A pass of this crate, configured by its own field of `Options`.
+struct BuiltinPass {
+    name: &'static str,
+    after: &'static [&'static str],
+    before: &'static [&'static str],
+    create: fn(&PassContext) -> Option<Pass>,
+}
+
+impl SvgrPass for BuiltinPass {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        self.after
+    }
+
+    fn before(&self) -> &'static [&'static str] {
+        self.before
+    }
+
+    fn create(&self, context: &PassContext) -> Result<Option<Pass>, ConfigError> {
+        Ok((self.create)(context))
+    }
+}
+
+fn element_pass(pass: impl ElementPass + 'static) -> Option<Pass> {
+    Some(Pass::Element(Box::new(pass)))
+}
+
+const BUILTIN_PASSES: [BuiltinPass; 21] = [
+    BuiltinPass {
+        name: "transform_svg_component",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .transform_svg_component
+                .clone()
+                .map(|config| Pass::Program(Box::new(TransformSVGComponentVisitor::new(config))))
+        },
+    },
+    BuiltinPass {
+        name: "cleanup",
+        after: &[],
+        // Descriptions are removed before `desc_prop` reuses them, and names
+        // compared before they are camelCased.
+        before: &["desc_prop", "transform_attribute"],
+        create: |context| {
+            context
+                .options
+                .cleanup
+                .clone()
+                .and_then(|options| element_pass(CleanupVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "inline_styles",
+        after: &[],
+        // Inlined declarations are hyphenated and merged into style strings,
+        // before they are camelCased and turned into objects.
+        before: &["remove_hidden_elements", "transform_attribute"],
+        create: |context| {
+            if !context.options.inline_styles {
+                return None;
+            }
+
+            element_pass(InlineStylesVisitor::default())
+        },
+    },
+    BuiltinPass {
+        name: "remove_hidden_elements",
+        after: &[],
+        before: &[],
+        create: |context| {
+            if !context.options.remove_hidden_elements {
+                return None;
+            }
+
+            element_pass(RemoveHiddenElementsVisitor::default())
+        },
+    },
+    BuiltinPass {
+        name: "remove_unused_ids",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .remove_unused_ids
+                .clone()
+                .and_then(|options| element_pass(RemoveUnusedIdsVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "prefix_ids",
+        after: &[],
+        before: &[],
+        create: |context| {
+            let component_name = context.options.transform_svg_component.as_ref().map_or(
+                DEFAULT_COMPONENT_NAME,
+                TransformSVGComponentOptions::component_name,
+            );
+            context
+                .options
+                .prefix_ids
+                .clone()
+                .and_then(|options| element_pass(PrefixIdsVisitor::new(options, component_name)))
+        },
+    },
+    BuiltinPass {
+        name: "convert_shapes",
+        after: &[],
+        // Converted before the path passes see them, and before `native`
+        // capitalizes the names.
+        before: &["minify_path_data", "native"],
+        create: |context| {
+            context
+                .options
+                .convert_shapes
+                .clone()
+                .and_then(|options| element_pass(ConvertShapesVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "minify_path_data",
+        after: &[],
+        // Only `path` elements are minified, not `Path` ones.
+        before: &["native"],
+        create: |context| {
+            context
+                .options
+                .minify_path_data
+                .clone()
+                .and_then(|options| element_pass(MinifyPathDataVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "cleanup_numeric_values",
+        after: &[],
+        // Attribute names are matched, and styles rounded, before they are
+        // camelCased and turned into objects.
+        before: &["transform_attribute"],
+        create: |context| {
+            context
+                .options
+                .cleanup_numeric_values
+                .clone()
+                .and_then(|options| element_pass(CleanupNumericValuesVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "collapse_groups",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .collapse_groups
+                .clone()
+                .and_then(|options| element_pass(CollapseGroupsVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "em_dimensions",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .em_dimensions
+                .clone()
+                .and_then(|options| element_pass(SVGEmDimensionsVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "remove_jsx_attribute",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .remove_jsx_attribute
+                .clone()
+                .and_then(|options| element_pass(RemoveJSXAttributeVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "add_jsx_attribute",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .add_jsx_attribute
+                .clone()
+                .and_then(|options| element_pass(AddJSXAttributeVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "remove_jsx_empty_expression",
+        after: &[],
+        before: &[],
+        create: |_| element_pass(RemoveEmptyExpressionVisitor),
+    },
+    BuiltinPass {
+        name: "replace_attribute_values",
+        after: &[],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .replace_attribute_values
+                .clone()
+                .and_then(|options| element_pass(ReplaceJSXAttributeValueVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "current_color",
+        // Colors replaced with `replaceAttrValues` take precedence.
+        after: &["replace_attribute_values"],
+        before: &[],
+        create: |context| {
+            context
+                .options
+                .current_color
+                .clone()
+                .and_then(|options| element_pass(CurrentColorVisitor::new(options)))
+        },
+    },
+    BuiltinPass {
+        name: "css_variables",
+        // The component the variables are listed on comes from
+        // `transform_svg_component`.
+        after: &["transform_svg_component", "replace_attribute_values"],
+        before: &[],
+        create: |context| {
+            let component_name = context.options.transform_svg_component.as_ref().map_or(
+                DEFAULT_COMPONENT_NAME,
+                TransformSVGComponentOptions::component_name,
+            );
+            context
+                .options
+                .css_variables
+                .clone()
+                .map(|options| CssVariablesVisitor::new(options, component_name))
+                .and_then(element_pass)
+        },
+    },
+    BuiltinPass {
+        name: "title_prop",
+        after: &[],
+        before: &[],
+        create: |context| {
+            if !context.options.title_prop {
+                return None;
+            }
+            element_pass(DynamicTitleVisitor::new(DynamicTitleOptions {
+                tag: "title".into(),
+            }))
+        },
+    },
+    BuiltinPass {
+        name: "desc_prop",
+        after: &[],
+        before: &[],
+        create: |context| {
+            if !context.options.desc_prop {
+                return None;
+            }
+            element_pass(DynamicTitleVisitor::new(DynamicTitleOptions {
+                tag: "desc".into(),
+            }))
+        },
+    },
+    BuiltinPass {
+        name: "native",
+        // The `react-native-svg` import to complete comes from the component.
+        after: &["transform_svg_component"],
+        before: &[],
+        create: |context| {
+            if !context.options.native {
+                return None;
+            }
+            element_pass(
+                TransformReactNativeSVGVisitor {
+                    comments: context.comments,
+                }
+                .into_pass(),
+            )
+        },
+    },
+    BuiltinPass {
+        name: "transform_attribute",
+        after: &[],
+        before: &[],
+        create: |_| element_pass(TransformAttributeVisitor),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform_svg;
+    use serde::Deserialize;
+    use swc_core::{
+        common::{sync::Lrc, SourceMap},
+        ecma::codegen::{text_writer::JsWriter, Emitter},
+    };
+
+    struct RenamePass {
+        after: &'static [&'static str],
+        before: &'static [&'static str],
+    }
+
+    #[derive(Deserialize)]
+    struct RenameOptions {
+        from: String,
+        to: String,
+    }
+
+    struct RenameVisitor(RenameOptions);
+
+    impl ElementPass for RenameVisitor {
+        fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+            for attr in jsx_element.opening.attrs.iter_mut() {
+                if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(ident),
+                    ..
+                }) = attr
+                {
+                    if ident.sym == *self.0.from {
+                        ident.sym = self.0.to.as_str().into();
+                    }
+                }
+            }
+        }
+    }
+
+    impl SvgrPass for RenamePass {
+        fn name(&self) -> &'static str {
+            "rename_attribute"
+        }
+
+        fn after(&self) -> &'static [&'static str] {
+            self.after
+        }
+
+        fn before(&self) -> &'static [&'static str] {
+            self.before
+        }
+
+        fn create(&self, context: &PassContext) -> Result<Option<Pass>, ConfigError> {
+            Ok(context
+                .section::<RenameOptions>(self.name())?
+                .map(|options| Pass::Element(Box::new(RenameVisitor(options)))))
+        }
+    }
+
+    fn names(registry: &PassRegistry) -> Vec<&'static str> {
+        registry
+            .order()
+            .unwrap()
+            .into_iter()
+            .map(|pass| pass.name())
+            .collect()
+    }
+
+    fn options_with(custom: Value) -> Options {
+        let mut config = serde_json::json!({
+            "title_prop": false,
+            "desc_prop": false,
+            "native": false,
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(custom.as_object().unwrap().clone());
+        parse_config(&config.to_string()).unwrap()
+    }
+
+    fn svg_attribute_names(program: &Program) -> Vec<String> {
+        let expr = program.as_module().unwrap().body[0]
+            .as_stmt()
+            .unwrap()
+            .as_expr()
+            .unwrap();
+        expr.expr
+            .as_jsx_element()
+            .unwrap()
+            .opening
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(ident),
+                    ..
+                }) => Some(ident.sym.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn print(program: &Program) -> String {
+        let cm: Lrc<SourceMap> = Default::default();
+        let mut buf = vec![];
+        {
+            let mut emitter = Emitter {
+                cfg: Default::default(),
+                cm: cm.clone(),
+                comments: None,
+                wr: JsWriter::new(cm, "\n", &mut buf, None),
+            };
+            emitter.emit_program(program).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// Runs every pass over the whole program before the next one, as the
+    /// fused pipeline must behave.
+    fn transform_sequentially(mut program: Program, options: &Options) -> Program {
+        let context = PassContext {
+            options,
+            comments: None,
+        };
+        for pass in PassRegistry::with_builtin_passes().order().unwrap() {
+            match pass.create(&context).unwrap() {
+                Some(Pass::Element(element_pass)) => {
+                    let mut pipeline = ElementPipeline::default();
+                    pipeline.push(pass.name(), element_pass);
+                    pipeline.run(&mut program);
+                }
+                Some(Pass::Program(mut program_pass)) => program_pass.run(&mut program),
+                None => {}
+            }
+        }
+        program
+    }
+
+    /// Asserts that the fused pipeline gives the same code as running the
+    /// passes one after another, and returns it.
+    fn transform_both_ways(source: &str, options: &Options) -> String {
+        let parse = || crate::parse_svg::parse_svg(source).unwrap();
+        let (fused, errors) = PassRegistry::with_builtin_passes().transform(parse(), options, None);
+        assert!(errors.is_empty());
+        let fused = print(&fused);
+        assert_eq!(fused, print(&transform_sequentially(parse(), options)));
+        fused
+    }
+
+    #[test]
+    fn give_the_same_result_as_sequential_passes() {
+        let source = r##"<svg width="24" height="24"><metadata><path d="M0 0"/></metadata><style>.a{fill:#063855}</style><foo><path d="M0 0"/><rect width="1" height="1"/></foo><g><g><rect class="a" x="0" y="0" width="10.000" height="10"/></g></g><G><Path d="M1 1"/></G><linearGradient id="b"><stop offset="0" stop-color="#fff"/></linearGradient><circle cx="5" cy="5" r="5" fill="url(#b)" stroke="red"/></svg>"##;
+        let options = |native: bool| {
+            options_with(serde_json::json!({
+                "transform_svg_component": { "native": native, "state": { "componentName": "SvgIcon" } },
+                "cleanup": {},
+                "inline_styles": true,
+                "convert_shapes": {},
+                "minify_path_data": {},
+                "cleanup_numeric_values": {},
+                "collapse_groups": {},
+                "em_dimensions": {},
+                "replace_attribute_values": { "values": [{ "value": "red", "newValue": "blue" }] },
+                "native": native,
+            }))
+        };
+
+        let code = transform_both_ways(source, &options(false));
+        assert!(code.contains("<foo>"));
+
+        let code = transform_both_ways(source, &options(true));
+        // Neither the children of dropped elements nor raw components are kept.
+        assert!(
+            code.contains("import Svg, { LinearGradient, Path, Stop } from \"react-native-svg\";")
+        );
+        assert!(!code.contains("M1 1"));
+
+        // Paths are merged once `inline_styles` removed the style sheet.
+        let code = transform_both_ways(
+            r#"<svg><style>.a{fill:red}</style><path class="a" d="M0 0h1v1z"/><path class="a" d="M5 5h1v1z"/></svg>"#,
+            &options_with(serde_json::json!({ "inline_styles": true, "collapse_groups": {} })),
+        );
+        assert!(code.contains("d=\"M0 0h1v1zM5 5h1v1z\""));
+    }
+
+    #[test]
+    fn remove_hidden_elements_styled_before_attributes_are_transformed() {
+        let code = transform_both_ways(
+            r#"<svg><path style="display:none" d="M0 0"/><rect style="opacity: 0" width="1" height="1"/><path style="opacity: 0.5" d="M1 1"/></svg>"#,
+            &options_with(serde_json::json!({ "remove_hidden_elements": true })),
+        );
+        assert_eq!(
+            code,
+            "<svg><path style={{\n    opacity: 0.5\n}} d=\"M1 1\"/></svg>;\n"
+        );
+    }
+
+    #[test]
+    fn prefix_ids_once_unused_and_hidden_elements_are_removed() {
+        let code = transform_both_ways(
+            r##"<svg><defs><linearGradient id="a"/><linearGradient id="b"/></defs><linearGradient id="c" display="none"/><path id="p" fill="url(#a)" style="stroke:url(#c)" d="M0 0"/></svg>"##,
+            &options_with(serde_json::json!({
+                "remove_unused_ids": {},
+                "remove_hidden_elements": true,
+                "prefix_ids": { "prefix": "icon-" },
+            })),
+        );
+        assert_eq!(
+            code,
+            "<svg><defs><linearGradient id=\"icon-a\"/></defs><linearGradient id=\"icon-c\" display=\"none\"/><path fill=\"url(#icon-a)\" style={{\n    stroke: \"url(#icon-c)\"\n}} d=\"M0 0\"/></svg>;\n"
+        );
+    }
+
+    #[test]
+    fn keep_default_order_of_builtin_passes() {
+        assert_eq!(
+            names(&PassRegistry::with_builtin_passes()),
+            [
+                "transform_svg_component",
+                "cleanup",
+                "inline_styles",
+                "remove_hidden_elements",
+                "remove_unused_ids",
+                "prefix_ids",
+                "convert_shapes",
+                "minify_path_data",
+                "cleanup_numeric_values",
+                "collapse_groups",
+                "em_dimensions",
+                "remove_jsx_attribute",
+                "add_jsx_attribute",
+                "remove_jsx_empty_expression",
+                "replace_attribute_values",
+                "current_color",
+                "css_variables",
+                "title_prop",
+                "desc_prop",
+                "native",
+                "transform_attribute",
+            ]
+        );
+    }
+
+    #[test]
+    fn order_custom_pass_by_constraints() {
+        let mut registry = PassRegistry::with_builtin_passes();
+        registry
+            .register(RenamePass {
+                after: &["em_dimensions"],
+                before: &["remove_jsx_attribute", "transform_attribute"],
+            })
+            .unwrap();
+        let names = names(&registry);
+        assert_eq!(names[11], "rename_attribute");
+        assert_eq!(names[12], "remove_jsx_attribute");
+    }
+
+    #[test]
+    fn run_custom_pass_with_its_config_section() {
+        let mut registry = PassRegistry::with_builtin_passes();
+        registry
+            .register(RenamePass {
+                after: &[],
+                before: &["transform_attribute"],
+            })
+            .unwrap();
+        let options = options_with(serde_json::json!({
+            "rename_attribute": { "from": "data-old", "to": "data-new" },
+        }));
+        let (program, errors) = registry.transform(
+            crate::parse_svg::parse_svg(r#"<svg data-old="a" stroke-width="2"></svg>"#).unwrap(),
+            &options,
+            None,
+        );
+        assert!(errors.is_empty());
+        assert_eq!(svg_attribute_names(&program), ["data-new", "strokeWidth"]);
+
+        // Without its section the pass is disabled.
+        let (program, _) = transform_svg(
+            r#"<svg data-old="a"></svg>"#,
+            options_with(serde_json::json!({})),
+        )
+        .unwrap();
+        assert_eq!(svg_attribute_names(&program), ["data-old"]);
+    }
+
+    #[test]
+    fn report_invalid_config_section() {
+        let mut registry = PassRegistry::default();
+        registry
+            .register(RenamePass {
+                after: &[],
+                before: &[],
+            })
+            .unwrap();
+        let (_, errors) = registry.transform(
+            crate::parse_svg::parse_svg("<svg></svg>").unwrap(),
+            &options_with(serde_json::json!({ "rename_attribute": { "from": 1 } })),
+            None,
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "rename_attribute.from");
+
+        // Unknown keys are collected for custom passes, but the paths of the
+        // built-in sections are still reported.
+        let err = parse_config::<Options>(
+            r#"{ "title_prop": false, "desc_prop": false, "native": false, "em_dimensions": { "width": [] } }"#,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.path, "em_dimensions.width");
+    }
+
+    #[test]
+    fn run_passes_listed_in_pipeline_option() {
+        let options = options_with(serde_json::json!({
+            "remove_jsx_attribute": { "elements": ["svg"], "attributes": ["strokeWidth"] },
+            "pipeline": ["transform_attribute", "remove_jsx_attribute"],
+        }));
+        let (program, errors) = PassRegistry::with_builtin_passes().transform(
+            crate::parse_svg::parse_svg(r#"<svg stroke-width="2" fill-rule="evenodd"></svg>"#)
+                .unwrap(),
+            &options,
+            None,
+        );
+        assert!(errors.is_empty());
+        assert_eq!(svg_attribute_names(&program), ["fillRule"]);
+    }
+
+    #[test]
+    fn reject_invalid_pipeline_option() {
+        let registry = PassRegistry::with_builtin_passes();
+        let errors = registry
+            .order_by(&[
+                "native".into(),
+                "transform_svg_component".into(),
+                "native".into(),
+                "svgo".into(),
+            ])
+            .err()
+            .unwrap();
+        assert_eq!(
+            errors.iter().map(|err| err.to_string()).collect::<Vec<_>>(),
+            [
+                "`pipeline[2]`: pass is listed more than once",
+                "`pipeline[3]`: unknown pass",
+                "`pipeline[0]`: `native` must run after `transform_svg_component`",
+            ]
+        );
+
+        let names = registry
+            .order_by(&["transform_svg_component".into(), "native".into()])
+            .unwrap()
+            .into_iter()
+            .map(|pass| pass.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["transform_svg_component", "native"]);
+    }
+
+    #[test]
+    fn reject_invalid_registrations() {
+        let mut registry = PassRegistry::with_builtin_passes();
+        registry
+            .register(RenamePass {
+                after: &["native"],
+                before: &["transform_svg_component"],
+            })
+            .unwrap();
+        assert_eq!(
+            registry.order().err(),
+            Some(RegistryError::Cycle(vec![
+                "transform_svg_component".into(),
+                "rename_attribute".into(),
+                "native".into(),
+            ]))
+        );
+        assert_eq!(
+            registry.register(RenamePass {
+                after: &[],
+                before: &[],
+            }),
+            Err(RegistryError::DuplicatePass("rename_attribute".into()))
+        );
+
+        let mut registry = PassRegistry::default();
+        registry
+            .register(RenamePass {
+                after: &["missing"],
+                before: &[],
+            })
+            .unwrap();
+        assert_eq!(
+            registry.order().err(),
+            Some(RegistryError::UnknownPass {
+                pass: "rename_attribute".into(),
+                constraint: "missing".into(),
+            })
+        );
+    }
+}
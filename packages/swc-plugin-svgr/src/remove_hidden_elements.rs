@@ -0,0 +1,259 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use std::collections::HashSet;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use crate::{
+    pipeline::{element_name, ElementPass},
+    references::{collect_references, element_id, has_expression_id},
+};
+
+/// Removes the children that can never render: elements with
+/// `display="none"` or `opacity="0"`, hidden elements without visible
+/// descendants, circles, ellipses and rects of zero size, paths without data,
+/// and empty groups and `<defs>`.
+///
+/// Elements holding an id referenced elsewhere in the document are kept, as a
+/// hidden gradient or path can still be used. So are transparent elements in
+/// a `<clipPath>`, as opacity does not apply to clip geometry.
+#[derive(Default)]
+pub struct RemoveHiddenElementsVisitor {
+    /// Collected when entering the root element.
+    references: Option<HashSet<String>>,
+    /// Number of `<clipPath>` elements the current element is in, itself
+    /// included.
+    clip_path_depth: usize,
+}
+
+impl RemoveHiddenElementsVisitor {
+    fn is_hidden(&self, child: &JSXElementChild) -> bool {
+        let child = match child {
+            JSXElementChild::JSXElement(child) => child,
+            _ => return false,
+        };
+        if self
+            .references
+            .as_ref()
+            .is_none_or(|references| has_referenced_id(child, references))
+        {
+            return false;
+        }
+
+        if presentation_attribute(child, "display").as_deref() == Some("none")
+            || (self.clip_path_depth == 0
+                && presentation_attribute(child, "opacity").is_some_and(|value| is_zero(&value)))
+            || (presentation_attribute(child, "visibility").as_deref() == Some("hidden")
+                && !has_visible_descendant(child))
+        {
+            return true;
+        }
+
+        match element_name(child).as_deref() {
+            Some("circle") => is_zero_attribute(child, "r"),
+            Some("ellipse") => is_zero_attribute(child, "rx") || is_zero_attribute(child, "ry"),
+            Some("rect") => is_zero_attribute(child, "width") || is_zero_attribute(child, "height"),
+            Some("path") => match attribute(child, "d") {
+                Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => value.trim().is_empty(),
+                Some(_) => false,
+                None => !has_spread(child),
+            },
+            Some("g" | "defs") => child.children.iter().all(|child| {
+                matches!(child, JSXElementChild::JSXText(JSXText { value, .. }) if value.trim().is_empty())
+            }),
+            _ => false,
+        }
+    }
+}
+
+impl ElementPass for RemoveHiddenElementsVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        if self.references.is_none() {
+            let mut references = HashSet::new();
+            collect_references(jsx_element, &mut references);
+            self.references = Some(references);
+        }
+        if is_clip_path(jsx_element) {
+            self.clip_path_depth += 1;
+        }
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.children.retain(|child| !self.is_hidden(child));
+        if is_clip_path(jsx_element) {
+            self.clip_path_depth -= 1;
+        }
+    }
+}
+
+impl VisitMut for RemoveHiddenElementsVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        self.enter_element(jsx_element);
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+fn attribute<'a>(jsx_element: &'a JSXElement, name: &str) -> Option<&'a JSXAttrValue> {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(Ident { sym, .. }),
+                value: Some(value),
+                ..
+            }) if sym == name => Some(value),
+            _ => None,
+        })
+}
+
+fn string_attribute<'a>(jsx_element: &'a JSXElement, name: &str) -> Option<&'a str> {
+    match attribute(jsx_element, name) {
+        Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => Some(value),
+        _ => None,
+    }
+}
+
+/// The value of a presentation attribute, from the inline style when it sets
+/// it, as it takes precedence.
+fn presentation_attribute(jsx_element: &JSXElement, name: &str) -> Option<String> {
+    let from_style = match attribute(jsx_element, "style") {
+        Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => {
+            value.split(';').rev().find_map(|declaration| {
+                let (property, value) = declaration.split_once(':')?;
+                (property.trim() == name).then(|| value.trim().to_string())
+            })
+        }
+        // Style objects, once `transform_attribute` ran on the children.
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        })) => match &**expr {
+            Expr::Object(ObjectLit { props, .. }) => props.iter().rev().find_map(|prop| {
+                let PropOrSpread::Prop(prop) = prop else {
+                    return None;
+                };
+                let Prop::KeyValue(KeyValueProp { key, value }) = &**prop else {
+                    return None;
+                };
+                let key = match key {
+                    PropName::Ident(Ident { sym, .. }) => &**sym,
+                    PropName::Str(Str { value, .. }) => &**value,
+                    _ => return None,
+                };
+                if key != name {
+                    return None;
+                }
+                match &**value {
+                    Expr::Lit(Lit::Str(Str { value, .. })) => Some(value.trim().to_string()),
+                    Expr::Lit(Lit::Num(Number { value, .. })) => Some(value.to_string()),
+                    _ => None,
+                }
+            }),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    from_style.or_else(|| string_attribute(jsx_element, name).map(|value| value.trim().to_string()))
+}
+
+fn is_clip_path(jsx_element: &JSXElement) -> bool {
+    element_name(jsx_element).as_deref() == Some("clippath")
+}
+
+fn has_spread(jsx_element: &JSXElement) -> bool {
+    jsx_element
+        .opening
+        .attrs
+        .iter()
+        .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)))
+}
+
+fn is_zero(value: &str) -> bool {
+    value
+        .trim()
+        .trim_end_matches("px")
+        .parse::<f64>()
+        .is_ok_and(|value| value == 0.0)
+}
+
+fn is_zero_attribute(jsx_element: &JSXElement, name: &str) -> bool {
+    string_attribute(jsx_element, name).is_some_and(is_zero)
+}
+
+fn has_referenced_id(jsx_element: &JSXElement, references: &HashSet<String>) -> bool {
+    element_id(jsx_element).is_some_and(|id| references.contains(id))
+        || has_expression_id(jsx_element)
+        || jsx_element.children.iter().any(|child| match child {
+            JSXElementChild::JSXElement(child) => has_referenced_id(child, references),
+            _ => false,
+        })
+}
+
+fn has_visible_descendant(jsx_element: &JSXElement) -> bool {
+    jsx_element.children.iter().any(|child| match child {
+        JSXElementChild::JSXElement(child) => {
+            presentation_attribute(child, "visibility").as_deref() == Some("visible")
+                || has_visible_descendant(child)
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveHiddenElementsVisitor::default()),
+        remove_hidden_elements,
+        r#"<svg><g id="Artboard" display="none"><path d="M0 0h1" /></g><rect style="opacity: 0" width="1" height="1" /><g visibility="hidden"><path d="M0 0h1" /></g><circle r="0" /><ellipse rx="1" ry="0px" /><rect width="0" height="10" /><path d=" " /><path /><defs /><g><g /></g><path d="M0 0h1" /></svg>;"#,
+        r#"<svg><path d="M0 0h1" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveHiddenElementsVisitor::default()),
+        keep_referenced_and_visible_elements,
+        r##"<svg><defs display="none"><linearGradient id="a" /></defs><g visibility="hidden"><path visibility="visible" d="M0 0h1" /></g><path display="none" id="p" d="M0 0h1" /><use href="#p" /><rect fill="url(#a)" width="1" height="1" style="display: none; display: block" /><circle r="1" /><path d={d} /></svg>;"##,
+        r##"<svg><defs display="none"><linearGradient id="a" /></defs><g visibility="hidden"><path visibility="visible" d="M0 0h1" /></g><path display="none" id="p" d="M0 0h1" /><use href="#p" /><rect fill="url(#a)" width="1" height="1" style="display: none; display: block" /><circle r="1" /><path d={d} /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveHiddenElementsVisitor::default()),
+        read_style_objects,
+        r#"<svg><path style={{ display: "none" }} d="M0 0h1" /><rect style={{ "opacity": 0 }} width="1" height="1" /><path style={{ opacity: 0.5 }} d="M0 0h1" /></svg>;"#,
+        r#"<svg><path style={{ opacity: 0.5 }} d="M0 0h1" /></svg>;"#
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveHiddenElementsVisitor::default()),
+        keep_transparent_clip_path_children,
+        r##"<svg><clipPath id="c"><rect opacity="0" width="5" height="5" /><g><circle style="opacity: 0" r="1" /></g><rect display="none" width="5" height="5" /></clipPath><path clip-path="url(#c)" d="M0 0h1" /><rect opacity="0" width="5" height="5" /></svg>;"##,
+        r##"<svg><clipPath id="c"><rect opacity="0" width="5" height="5" /><g><circle style="opacity: 0" r="1" /></g></clipPath><path clip-path="url(#c)" d="M0 0h1" /></svg>;"##
+    );
+}
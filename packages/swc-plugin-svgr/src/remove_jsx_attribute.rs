@@ -1,38 +1,173 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use swc_core::ecma::{
     ast::*,
     visit::{VisitMut, VisitMutWith},
 };
-#[derive(Deserialize)]
+use transform_svg_component::error::ConfigError;
+
+use crate::pipeline::ElementPass;
+
+/// Names and values are patterns: `/regex/` is a regular expression, a
+/// pattern with `*` or `?` is a glob (`data-*`), anything else is compared as
+/// is. Namespaced attributes are named `xlink:title`.
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
     elements: Vec<String>,
     attributes: Vec<String>,
+    /// Removes the attributes only when their value matches it, e.g. `#000`.
+    /// Values given as expressions other than numbers never match.
+    #[serde(default)]
+    value: Option<String>,
+}
+
+enum Pattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Result<Self, regex::Error> {
+        if let Some(regex) = pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            return Regex::new(regex).map(Pattern::Regex);
+        }
+        if !pattern.contains(['*', '?']) {
+            return Ok(Pattern::Exact(pattern.to_string()));
+        }
+
+        let mut regex = String::from("^");
+        for c in pattern.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        regex.push('$');
+        Regex::new(&regex).map(Pattern::Regex)
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Pattern::Exact(pattern) => pattern == value,
+            Pattern::Regex(regex) => regex.is_match(value),
+        }
+    }
 }
 
 pub struct RemoveJSXAttributeVisitor {
-    pub options: Options,
+    elements: Vec<Pattern>,
+    attributes: Vec<Pattern>,
+    value: Option<Pattern>,
+    errors: Vec<ConfigError>,
 }
 
-impl VisitMut for RemoveJSXAttributeVisitor {
-    fn visit_mut_jsx_opening_element(&mut self, jsx_opening_element: &mut JSXOpeningElement) {
-        jsx_opening_element.visit_mut_children_with(self);
-        if let JSXElementName::Ident(Ident { sym, .. }) = &jsx_opening_element.name {
-            if self.options.elements.contains(&sym.to_string()) {
-                jsx_opening_element.attrs.retain(|attr| {
-                    if let JSXAttrOrSpread::JSXAttr(JSXAttr {
-                        name: JSXAttrName::Ident(Ident { sym, .. }),
-                        ..
-                    }) = attr
-                    {
-                        !self.options.attributes.contains(&sym.to_string())
-                    } else {
-                        true
-                    }
-                })
+impl RemoveJSXAttributeVisitor {
+    pub fn new(options: Options) -> Self {
+        let mut errors = vec![];
+        let mut compile = |path: String, pattern: &str| match Pattern::new(pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(_) => {
+                errors.push(
+                    ConfigError::new(path, "invalid regular expression")
+                        .with_value(format!("\"{}\"", pattern))
+                        .with_hint("patterns use the syntax of the Rust `regex` crate"),
+                );
+                None
+            }
+        };
+        let mut compile_all = |section: &str, patterns: &[String]| {
+            patterns
+                .iter()
+                .enumerate()
+                .filter_map(|(index, pattern)| compile(format!("{}[{}]", section, index), pattern))
+                .collect::<Vec<_>>()
+        };
+        let elements = compile_all("elements", &options.elements);
+        let attributes = compile_all("attributes", &options.attributes);
+        let value = options
+            .value
+            .as_ref()
+            .and_then(|pattern| compile("value".to_string(), pattern));
+
+        RemoveJSXAttributeVisitor {
+            elements,
+            attributes,
+            value,
+            errors,
+        }
+    }
+
+    fn is_removed(&self, jsx_attr: &JSXAttr) -> bool {
+        let name = match &jsx_attr.name {
+            JSXAttrName::Ident(Ident { sym, .. }) => sym.to_string(),
+            JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+                format!("{}:{}", ns.sym, name.sym)
+            }
+        };
+        if !self
+            .attributes
+            .iter()
+            .any(|pattern| pattern.is_match(&name))
+        {
+            return false;
+        }
+
+        let Some(pattern) = &self.value else {
+            return true;
+        };
+        match &jsx_attr.value {
+            Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => pattern.is_match(value),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) => match &**expr {
+                Expr::Lit(Lit::Str(Str { value, .. })) => pattern.is_match(value),
+                Expr::Lit(Lit::Num(Number { value, .. })) => pattern.is_match(&value.to_string()),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ElementPass for RemoveJSXAttributeVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        let element = match &jsx_element.opening.name {
+            JSXElementName::Ident(Ident { sym, .. }) => sym.to_string(),
+            JSXElementName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+                format!("{}:{}", ns.sym, name.sym)
             }
+            JSXElementName::JSXMemberExpr(_) => return,
+        };
+        if !self
+            .elements
+            .iter()
+            .any(|pattern| pattern.is_match(&element))
+        {
+            return;
         }
+
+        jsx_element.opening.attrs.retain(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(jsx_attr) => !self.is_removed(jsx_attr),
+            JSXAttrOrSpread::SpreadElement(_) => true,
+        });
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for RemoveJSXAttributeVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
     }
 }
 
@@ -50,12 +185,11 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(RemoveJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["span".into()],
-                attributes: vec!["foo".into()]
-            }
-        }),
+        |_| as_folder(RemoveJSXAttributeVisitor::new(Options {
+            elements: vec!["span".into()],
+            attributes: vec!["foo".into()],
+            value: None,
+        })),
         remove_attributes_from_an_element,
         // Input codes
         r#"<div foo><span foo /></div>"#,
@@ -68,14 +202,61 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(RemoveJSXAttributeVisitor {
-            options: Options {
-                elements: vec!["span".into()],
-                attributes: vec!["foo".into()]
-            }
-        }),
+        |_| as_folder(RemoveJSXAttributeVisitor::new(Options {
+            elements: vec!["span".into()],
+            attributes: vec!["foo".into()],
+            value: None,
+        })),
         not_throw_error_when_spread_operator_is_used,
         r#"<div foo><span foo {...props} /></div>"#,
         r#"<div foo><span {...props} /></div>;"#
     );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveJSXAttributeVisitor::new(Options {
+            elements: vec!["*".into()],
+            attributes: vec![
+                "data-*".into(),
+                "xlink:title".into(),
+                "/^aria-(label|hidden)$/".into()
+            ],
+            value: None,
+        })),
+        remove_attributes_matching_patterns,
+        r##"<svg data-name="Layer 1" aria-hidden="true" aria-describedby="a"><path xlink:title="Path" xlink:href="#a" data-id={id} /></svg>"##,
+        r##"<svg aria-describedby="a"><path xlink:href="#a"/></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveJSXAttributeVisitor::new(Options {
+            elements: vec!["path".into(), "circle".into()],
+            attributes: vec!["fill".into(), "strokeWidth".into()],
+            value: Some("/^(#000|1)$/".into()),
+        })),
+        remove_attributes_with_value,
+        r##"<svg fill="#000"><path fill="#000" strokeWidth={1} /><circle fill="#fff" strokeWidth={2} /></svg>"##,
+        r##"<svg fill="#000"><path/><circle fill="#fff" strokeWidth={2}/></svg>;"##
+    );
+
+    #[test]
+    fn collect_error_for_invalid_pattern() {
+        let mut visitor = RemoveJSXAttributeVisitor::new(Options {
+            elements: vec!["svg".into()],
+            attributes: vec!["fill".into(), "/(/".into()],
+            value: None,
+        });
+
+        let errors = visitor.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "attributes[1]");
+        assert_eq!(errors[0].value.as_deref(), Some("\"/(/\""));
+    }
 }
@@ -6,18 +6,25 @@ use swc_core::{
     },
 };
 
-pub struct RemoveEmptyExpressionVisitor;
+use crate::pipeline::ElementPass;
 
-impl VisitMut for RemoveEmptyExpressionVisitor {
-    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
-        jsx_element.visit_mut_children_with(self);
+pub struct RemoveEmptyExpressionVisitor;
 
+impl ElementPass for RemoveEmptyExpressionVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
         jsx_element.children.retain(|element| {
             !matches!(element, JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::JSXEmptyExpr(_), .. }))
         })
     }
 }
 
+impl VisitMut for RemoveEmptyExpressionVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,169 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::error::ConfigError;
+
+use crate::{
+    pipeline::{element_name, ElementPass},
+    references::{collect_references, element_id, has_expression_id},
+};
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct Options {
+    /// Regular expressions of the ids to keep even when nothing refers to
+    /// them, e.g. `^icon-`.
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+/// Removes the `id` attributes nothing refers to, and the `<defs>` children
+/// holding no referenced id, as they can never render.
+///
+/// An id is referenced by `url(#id)` values, `href` and `xlink:href`
+/// attributes, the id lists of `aria-labelledby` and similar attributes, SMIL
+/// timing and `<style>` contents. Ids given as expressions, such as the
+/// `titleId` of `title_prop`, are left as is, and so are the `<defs>` children
+/// holding them.
+pub struct RemoveUnusedIdsVisitor {
+    keep: Vec<Regex>,
+    /// Collected when entering the root element.
+    references: Option<HashSet<String>>,
+    errors: Vec<ConfigError>,
+}
+
+impl RemoveUnusedIdsVisitor {
+    pub fn new(options: Options) -> Self {
+        let mut keep = vec![];
+        let mut errors = vec![];
+        for (index, pattern) in options.keep.iter().enumerate() {
+            match Regex::new(pattern) {
+                Ok(regex) => keep.push(regex),
+                Err(_) => errors.push(
+                    ConfigError::new(format!("keep[{}]", index), "invalid regular expression")
+                        .with_value(format!("\"{}\"", pattern))
+                        .with_hint("patterns use the syntax of the Rust `regex` crate"),
+                ),
+            }
+        }
+
+        RemoveUnusedIdsVisitor {
+            keep,
+            references: None,
+            errors,
+        }
+    }
+
+    fn is_used(&self, id: &str) -> bool {
+        self.references
+            .as_ref()
+            .is_none_or(|references| references.contains(id))
+            || self.keep.iter().any(|regex| regex.is_match(id))
+    }
+
+    fn has_used_id(&self, jsx_element: &JSXElement) -> bool {
+        element_id(jsx_element).is_some_and(|id| self.is_used(id))
+            || has_expression_id(jsx_element)
+            || jsx_element.children.iter().any(|child| match child {
+                JSXElementChild::JSXElement(child) => self.has_used_id(child),
+                _ => false,
+            })
+    }
+}
+
+impl ElementPass for RemoveUnusedIdsVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        if self.references.is_none() {
+            let mut references = HashSet::new();
+            collect_references(jsx_element, &mut references);
+            self.references = Some(references);
+        }
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        if element_name(jsx_element).as_deref() == Some("defs") {
+            jsx_element.children.retain(|child| match child {
+                JSXElementChild::JSXElement(child) => {
+                    element_name(child).as_deref() == Some("style") || self.has_used_id(child)
+                }
+                _ => true,
+            });
+        }
+
+        if element_id(jsx_element).is_some_and(|id| !self.is_used(id)) {
+            jsx_element.opening.attrs.retain(|attr| {
+                !matches!(
+                    attr,
+                    JSXAttrOrSpread::JSXAttr(JSXAttr {
+                        name: JSXAttrName::Ident(Ident { sym, .. }),
+                        value: Some(JSXAttrValue::Lit(_)),
+                        ..
+                    }) if sym == "id"
+                )
+            });
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for RemoveUnusedIdsVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        self.enter_element(jsx_element);
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::ecma::{
+        parser::{EsConfig, Syntax},
+        transforms::testing::test,
+        visit::as_folder,
+    };
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveUnusedIdsVisitor::new(Options::default())),
+        remove_unused_ids,
+        r##"<svg id="Page-1"><defs><linearGradient id="a"><stop offset="0" /></linearGradient><linearGradient id="b" /><path id="p" d="M0 0h1" /><filter id="unused" /><style>{".st0{fill:url(#c)}"}</style></defs><g id="Shape" fill="url('#a')"><use xlinkHref="#p" /><rect id="c" className="st0" /></g><path id="label" d="M0 0h1" /><text aria-labelledby="x label" /></svg>;"##,
+        r##"<svg><defs><linearGradient id="a"><stop offset="0" /></linearGradient><path id="p" d="M0 0h1" /><style>{".st0{fill:url(#c)}"}</style></defs><g fill="url('#a')"><use xlinkHref="#p" /><rect id="c" className="st0" /></g><path id="label" d="M0 0h1" /><text aria-labelledby="x label" /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(RemoveUnusedIdsVisitor::new(Options {
+            keep: vec!["^icon-".to_string()]
+        })),
+        keep_ids_matching_pattern,
+        r#"<svg id="icon-star"><defs><clipPath id="icon-clip" /><clipPath id="clip" /><clipPath id={clipId} /></defs><title id={titleId}>{title}</title><g id="Group" /></svg>;"#,
+        r#"<svg id="icon-star"><defs><clipPath id="icon-clip" /><clipPath id={clipId} /></defs><title id={titleId}>{title}</title><g /></svg>;"#
+    );
+
+    #[test]
+    fn report_invalid_pattern() {
+        let mut visitor = RemoveUnusedIdsVisitor::new(Options {
+            keep: vec!["^icon-".to_string(), "(".to_string()],
+        });
+
+        let errors = visitor.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "keep[1]");
+        assert_eq!(errors[0].value.as_deref(), Some("\"(\""));
+        assert_eq!(visitor.keep.len(), 1);
+    }
+}
@@ -1,54 +1,347 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
-use serde::Deserialize;
-use swc_common::{DUMMY_SP, input::StringInput, BytePos};
-use swc_core::{
-    ecma::{
-        ast::*,
-        visit::{VisitMut, VisitMutWith},
-        parser::{EsConfig, Parser, Syntax},
-    }
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use swc_common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+use transform_svg_component::{error::ConfigError, use_id::template_element};
+
+use crate::{
+    expression::parse_expression,
+    pipeline::{element_name, normalize, ElementPass},
+    references::attribute_name,
 };
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum NewValue {
     Boolean(bool),
     Number(f64),
     String(String),
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Value {
     value: String,
     new_value: NewValue,
     literal: Option<bool>,
+    /// Matches `value` as a regular expression, anywhere in the attribute
+    /// value. A string `new_value` replaces each match and can refer to
+    /// capture groups with `$1` or `${name}`.
+    regex: Option<bool>,
+    ignore_case: Option<bool>,
+    /// Names of the attributes, and style properties, the value is replaced
+    /// in. All of them when unset.
+    attributes: Option<Vec<String>>,
+    /// Names of the elements the value is replaced in. All of them when unset.
+    elements: Option<Vec<String>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
     pub values: Vec<Value>,
 }
 
+enum Pattern {
+    Exact(String),
+    IgnoreCase(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Pattern::Exact(pattern) => value == pattern,
+            Pattern::IgnoreCase(pattern) => value.to_lowercase() == *pattern,
+            Pattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+/// Replaces the attribute values, and the values of inline style properties,
+/// matching `options.values`. When several values match, the last one wins.
 pub struct ReplaceJSXAttributeValueVisitor {
     pub options: Options,
+    pub errors: Vec<ConfigError>,
+    /// Built once from `options.values`, in the same order. `None` when the
+    /// pattern is invalid.
+    patterns: Vec<Option<Pattern>>,
+    new_values: Vec<Result<Option<JSXAttrValue>, ConfigError>>,
 }
 
-impl VisitMut for ReplaceJSXAttributeValueVisitor {
-    fn visit_mut_jsx_attr(&mut self, jsx_attr: &mut JSXAttr) {
-        jsx_attr.visit_mut_children_with(self);
-        if let Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) = &jsx_attr.clone().value {
-            for value_option in &self.options.values {
-                if value.eq(value_option.value.as_str()) {
-                    jsx_attr.value =
-                        get_attribute_value(&value_option.new_value, value_option.literal);
+impl ReplaceJSXAttributeValueVisitor {
+    pub fn new(options: Options) -> Self {
+        let mut errors = vec![];
+        let patterns = options
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value_option)| {
+                let ignore_case = value_option.ignore_case.unwrap_or(false);
+                if !value_option.regex.unwrap_or(false) {
+                    return Some(if ignore_case {
+                        Pattern::IgnoreCase(value_option.value.to_lowercase())
+                    } else {
+                        Pattern::Exact(value_option.value.clone())
+                    });
+                }
+
+                match RegexBuilder::new(&value_option.value)
+                    .case_insensitive(ignore_case)
+                    .build()
+                {
+                    Ok(regex) => Some(Pattern::Regex(regex)),
+                    Err(_) => {
+                        errors.push(
+                            ConfigError::new(
+                                format!("values[{}].value", index),
+                                "invalid regular expression",
+                            )
+                            .with_value(format!("\"{}\"", value_option.value))
+                            .with_hint("patterns use the syntax of the Rust `regex` crate"),
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        let new_values = options
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value_option)| {
+                get_attribute_value(&value_option.new_value, value_option.literal)
+                    .map_err(|err| err.nested(&format!("values[{}]", index)))
+            })
+            .collect();
+
+        ReplaceJSXAttributeValueVisitor {
+            options,
+            errors,
+            patterns,
+            new_values,
+        }
+    }
+
+    /// The value replacing `value` of the attribute or style property `name`
+    /// of `element`, when one of `options.values` matches it.
+    fn replacement(
+        &mut self,
+        element: Option<&str>,
+        name: &str,
+        value: &str,
+    ) -> Option<Option<JSXAttrValue>> {
+        let mut replacement = None;
+        for (index, value_option) in self.options.values.iter().enumerate() {
+            let pattern = match &self.patterns[index] {
+                Some(pattern) if pattern.is_match(value) => pattern,
+                _ => continue,
+            };
+            let applies = |names: &Option<Vec<String>>, name: Option<&str>| {
+                names.as_ref().is_none_or(|names| {
+                    names
+                        .iter()
+                        .any(|other| Some(normalize(other).as_str()) == name)
+                })
+            };
+            if !applies(&value_option.attributes, Some(name))
+                || !applies(&value_option.elements, element)
+            {
+                continue;
+            }
+
+            let new_value = match (pattern, &value_option.new_value) {
+                (Pattern::Regex(regex), NewValue::String(new_value)) => {
+                    if value_option.literal.unwrap_or(false) {
+                        let mut expanded = String::new();
+                        if let Some(captures) = regex.captures(value) {
+                            captures.expand(new_value, &mut expanded);
+                        }
+                        get_attribute_value(&NewValue::String(expanded), Some(true))
+                            .map_err(|err| err.nested(&format!("values[{}]", index)))
+                    } else {
+                        Ok(Some(JSXAttrValue::Lit(Lit::Str(Str {
+                            span: DUMMY_SP,
+                            value: regex.replace_all(value, new_value.as_str()).into(),
+                            raw: None,
+                        }))))
+                    }
                 }
+                _ => self.new_values[index].clone(),
+            };
+            match new_value {
+                Ok(new_value) => replacement = Some(new_value),
+                Err(err) => {
+                    if !self.errors.contains(&err) {
+                        self.errors.push(err);
+                    }
+                }
+            }
+        }
+        replacement
+    }
+
+    fn replace_value(&mut self, element: Option<&str>, jsx_attr: &mut JSXAttr) {
+        let name = attribute_name(&jsx_attr.name);
+        match &mut jsx_attr.value {
+            Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => {
+                let value = value.to_string();
+                if let Some(new_value) = self.replacement(element, &name, &value) {
+                    jsx_attr.value = new_value;
+                } else if name == "style" {
+                    jsx_attr.value = match self.replace_style(element, &value) {
+                        Some(Expr::Lit(lit)) => Some(JSXAttrValue::Lit(lit)),
+                        Some(style) => Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                            span: DUMMY_SP,
+                            expr: JSXExpr::Expr(Box::new(style)),
+                        })),
+                        None => return,
+                    };
+                }
+            }
+            // Style objects, once `transform_attribute` ran.
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) if name == "style" => {
+                if let Expr::Object(ObjectLit { props, .. }) = &mut **expr {
+                    for prop in props.iter_mut() {
+                        if let PropOrSpread::Prop(prop) = prop {
+                            if let Prop::KeyValue(KeyValueProp { key, value }) = &mut **prop {
+                                let key = match key {
+                                    PropName::Ident(Ident { sym, .. }) => normalize(sym),
+                                    PropName::Str(Str { value, .. }) => normalize(value),
+                                    _ => continue,
+                                };
+                                let string = match &**value {
+                                    Expr::Lit(Lit::Str(Str { value, .. })) => value.to_string(),
+                                    Expr::Lit(Lit::Num(Number { value, .. })) => value.to_string(),
+                                    _ => continue,
+                                };
+                                if let Some(Some(new_value)) =
+                                    self.replacement(element, &key, &string)
+                                {
+                                    **value = expression(new_value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A style string with its matching declaration values replaced: a string
+    /// when they are replaced by strings, otherwise a template literal, which
+    /// `transform_attribute` turns into a style object like the others.
+    fn replace_style(&mut self, element: Option<&str>, css: &str) -> Option<Expr> {
+        let mut quasis = vec![String::new()];
+        let mut exprs = vec![];
+        let mut replaced = false;
+        for (index, declaration) in css.split(';').enumerate() {
+            let quasi = quasis.last_mut().unwrap();
+            if index > 0 {
+                quasi.push(';');
             }
+            let Some((property, value)) = declaration.split_once(':') else {
+                quasi.push_str(declaration);
+                continue;
+            };
+            let trimmed = value.trim();
+            let new_value = self.replacement(element, &normalize(property), trimmed);
+            let Some(Some(new_value)) = new_value else {
+                quasi.push_str(declaration);
+                continue;
+            };
+
+            replaced = true;
+            let start = value.len() - value.trim_start().len();
+            quasi.push_str(property);
+            quasi.push(':');
+            quasi.push_str(&value[..start]);
+            match new_value {
+                JSXAttrValue::Lit(Lit::Str(Str {
+                    value: new_value, ..
+                })) => quasi.push_str(&new_value),
+                new_value => {
+                    exprs.push(Box::new(expression(new_value)));
+                    quasis.push(String::new());
+                }
+            }
+            quasis
+                .last_mut()
+                .unwrap()
+                .push_str(&value[start + trimmed.len()..]);
+        }
+        if !replaced {
+            return None;
         }
+
+        Some(if exprs.is_empty() {
+            Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: quasis.remove(0).into(),
+                raw: None,
+            }))
+        } else {
+            let last = quasis.len() - 1;
+            Expr::Tpl(Tpl {
+                span: DUMMY_SP,
+                exprs,
+                quasis: quasis
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, quasi)| template_element(quasi, index == last))
+                    .collect(),
+            })
+        })
     }
 }
 
-fn get_attribute_value(new_value: &NewValue, literal_option: Option<bool>) -> Option<JSXAttrValue> {
-    match new_value {
+impl ElementPass for ReplaceJSXAttributeValueVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        let element = element_name(jsx_element);
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
+                self.replace_value(element.as_deref(), jsx_attr);
+            }
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<ConfigError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl VisitMut for ReplaceJSXAttributeValueVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
+/// The expression of an attribute value, to put in a style object.
+fn expression(value: JSXAttrValue) -> Expr {
+    match value {
+        JSXAttrValue::Lit(lit) => Expr::Lit(lit),
+        JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        }) => *expr,
+        JSXAttrValue::JSXExprContainer(_) => Expr::Ident(Ident::new("undefined".into(), DUMMY_SP)),
+        JSXAttrValue::JSXElement(element) => Expr::JSXElement(element),
+        JSXAttrValue::JSXFragment(fragment) => Expr::JSXFragment(fragment),
+    }
+}
+
+fn get_attribute_value(
+    new_value: &NewValue,
+    literal_option: Option<bool>,
+) -> Result<Option<JSXAttrValue>, ConfigError> {
+    Ok(match new_value {
         NewValue::String(string_value) => {
             let string_value_literal = Some(JSXAttrValue::Lit(Lit::Str(Str {
                 span: DUMMY_SP,
@@ -58,36 +351,18 @@ fn get_attribute_value(new_value: &NewValue, literal_option: Option<bool>) -> Op
 
             if let Some(literal) = literal_option {
                 if literal {
-                    let mut parser = Parser::new(
-                        Syntax::Es(EsConfig {
-                            jsx: false,
-                            fn_bind: false,
-                            decorators: false,
-                            decorators_before_export: false,
-                            export_default_from: true,
-                            import_assertions: false,
-                            allow_return_outside_function: false,
-                            allow_super_outside_method: false,
-                            auto_accessors: false,
-                        }),
-                        StringInput::new(string_value, BytePos(0), BytePos(0)),
-                        None,
-                    );
-
-                    let ast = parser.parse_module().unwrap_or_else(|_| {
-                        panic!(
-                            "Failed to parse  newValue \"{}\"",
-                            string_value,
-                        )
-                    });
-                    if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &ast.body[0] {
-                        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    let expr = parse_expression(string_value).map_err(|_| {
+                        ConfigError::new("newValue", "failed to parse newValue expression")
+                            .with_value(format!("\"{}\"", string_value))
+                            .with_hint("a literal newValue must be a valid JavaScript expression, or set `literal: false`")
+                    })?;
+
+                    expr.map(|expr| {
+                        JSXAttrValue::JSXExprContainer(JSXExprContainer {
                             span: DUMMY_SP,
-                            expr: JSXExpr::Expr(expr.clone()),
-                        }))
-                    } else {
-                        None
-                    }
+                            expr: JSXExpr::Expr(expr),
+                        })
+                    })
                 } else {
                     string_value_literal
                 }
@@ -104,16 +379,19 @@ fn get_attribute_value(new_value: &NewValue, literal_option: Option<bool>) -> Op
             span: DUMMY_SP,
             expr: JSXExpr::Expr(Box::new(Expr::Lit(Lit::Num((*number_value).into())))),
         })),
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use swc_core::ecma::{
-        parser::{EsConfig, Syntax},
-        transforms::testing::test,
-        visit::as_folder,
+    use swc_core::{
+        common::{input::StringInput, BytePos},
+        ecma::{
+            parser::{EsConfig, Parser, Syntax},
+            transforms::testing::test,
+            visit::as_folder,
+        },
     };
 
     test!(
@@ -121,15 +399,17 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(ReplaceJSXAttributeValueVisitor {
-            options: Options {
-                values: vec![Value {
-                    value: "cool".into(),
-                    new_value: NewValue::String("not cool".into()),
-                    literal: None,
-                }]
-            }
-        }),
+        |_| as_folder(ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![Value {
+                value: "cool".into(),
+                new_value: NewValue::String("not cool".into()),
+                literal: None,
+                regex: None,
+                ignore_case: None,
+                attributes: None,
+                elements: None,
+            }]
+        })),
         replace_attribute_values,
         // Input codes
         r#"<div something="cool" a={b}/>"#,
@@ -142,19 +422,133 @@ mod tests {
             jsx: true,
             ..Default::default()
         }),
-        |_| as_folder(ReplaceJSXAttributeValueVisitor {
-            options: Options {
-                values: vec![Value {
-                    value: "cool".into(),
-                    new_value: NewValue::String("props.color".into()),
-                    literal: Some(true),
-                }]
-            }
-        }),
+        |_| as_folder(ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![Value {
+                value: "cool".into(),
+                new_value: NewValue::String("props.color".into()),
+                literal: Some(true),
+                regex: None,
+                ignore_case: None,
+                attributes: None,
+                elements: None,
+            }]
+        })),
         replace_attribute_values_with_literal,
         // Input codes
         r#"<div something="cool" />"#,
         // Output codes after transformed with plugin
         r#"<div something={props.color} />;"#
     );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![Value {
+                value: "^#([0-9a-f]{6})$".into(),
+                new_value: NewValue::String("var(--color-$1)".into()),
+                literal: None,
+                regex: Some(true),
+                ignore_case: Some(true),
+                attributes: Some(vec!["fill".into(), "stop-color".into()]),
+                elements: Some(vec!["path".into(), "stop".into()]),
+            }]
+        })),
+        replace_attribute_values_with_regex,
+        r##"<svg fill="#063855"><stop stopColor="#FFFFFF" /><path fill="#063855" stroke="#063855" style="stop-color: #063855; stroke: #063855" /><path style={{ fill: "#063855" }} /></svg>;"##,
+        r##"<svg fill="#063855"><stop stopColor="var(--color-FFFFFF)" /><path fill="var(--color-063855)" stroke="#063855" style="stop-color: var(--color-063855); stroke: #063855" /><path style={{ fill: "var(--color-063855)" }} /></svg>;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![
+                Value {
+                    value: "red".into(),
+                    new_value: NewValue::String("props.color".into()),
+                    literal: Some(true),
+                    regex: None,
+                    ignore_case: Some(true),
+                    attributes: None,
+                    elements: None,
+                },
+                Value {
+                    value: "(\\d+)px".into(),
+                    new_value: NewValue::String("sizes[$1]".into()),
+                    literal: Some(true),
+                    regex: Some(true),
+                    ignore_case: None,
+                    attributes: Some(vec!["strokeWidth".into()]),
+                    elements: None,
+                },
+            ]
+        })),
+        replace_attribute_values_in_styles,
+        r##"<svg><path fill="RED" style="fill: Red; opacity: 0.5" /><path style={{ fill: "red", strokeWidth: "2px" }} /></svg>;"##,
+        r##"<svg><path fill={props.color} style={`fill: ${props.color}; opacity: 0.5`} /><path style={{ fill: props.color, strokeWidth: sizes[2] }} /></svg>;"##
+    );
+
+    #[test]
+    fn collect_error_for_invalid_regex() {
+        let visitor = ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![Value {
+                value: "#(".into(),
+                new_value: NewValue::String("red".into()),
+                literal: None,
+                regex: Some(true),
+                ignore_case: None,
+                attributes: None,
+                elements: None,
+            }],
+        });
+
+        assert_eq!(visitor.errors.len(), 1);
+        assert_eq!(
+            visitor.errors[0]
+                .clone()
+                .nested("replace_attribute_values")
+                .path,
+            "replace_attribute_values.values[0].value"
+        );
+    }
+
+    #[test]
+    fn collect_error_for_invalid_literal() {
+        let mut visitor = ReplaceJSXAttributeValueVisitor::new(Options {
+            values: vec![Value {
+                value: "cool".into(),
+                new_value: NewValue::String("props.".into()),
+                literal: Some(true),
+                regex: None,
+                ignore_case: None,
+                attributes: None,
+                elements: None,
+            }],
+        });
+        let mut parser = Parser::new(
+            Syntax::Es(EsConfig {
+                jsx: true,
+                ..Default::default()
+            }),
+            StringInput::new(r#"<div a="cool" b="cool" />"#, BytePos(0), BytePos(0)),
+            None,
+        );
+        let mut expr = parser.parse_expr().unwrap();
+
+        expr.visit_mut_with(&mut visitor);
+
+        assert_eq!(visitor.errors.len(), 1);
+        assert_eq!(
+            visitor.errors[0]
+                .clone()
+                .nested("replace_attribute_values")
+                .path,
+            "replace_attribute_values.values[0].newValue"
+        );
+    }
 }
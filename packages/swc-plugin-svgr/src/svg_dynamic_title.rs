@@ -1,9 +1,11 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
-use serde::Deserialize;
-use swc_common::DUMMY_SP;
+use serde::{Deserialize, Serialize};
+use swc_common::{util::take::Take, DUMMY_SP};
 use swc_core::ecma::{ast::*, visit::VisitMut};
 
-#[derive(Deserialize)]
+use crate::pipeline::{is_element_of, ElementPass};
+
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
     #[serde(default = "default_tag")]
     pub tag: String,
@@ -16,6 +18,9 @@ fn default_tag() -> String {
 pub struct DynamicTitleVisitor {
     elements: Vec<String>,
     options: Options,
+    /// Nesting of the JSX element being visited, as only outermost elements
+    /// get a dynamic title.
+    depth: usize,
 }
 
 impl DynamicTitleVisitor {
@@ -23,19 +28,46 @@ impl DynamicTitleVisitor {
         DynamicTitleVisitor {
             elements: vec!["svg".into(), "Svg".into()],
             options,
+            depth: 0,
         }
     }
 
-    fn get_tag_element(
-        &mut self,
-        existing_title_option: &mut Option<JSXElement>,
-    ) -> JSXExprContainer {
-        let tag_name = &self.options.tag;
+    fn add_tag_element(&self, jsx_element: &mut JSXElement) {
+        let tag_name = self.options.tag.as_str();
+        let title_element_position = jsx_element.children.iter().position(|child| {
+            matches!(
+                child,
+                JSXElementChild::JSXElement(child_element)
+                    if matches!(
+                        &child_element.opening.name,
+                        JSXElementName::Ident(Ident { sym, .. }) if *sym == *tag_name
+                    )
+            )
+        });
 
-        if let Some(existing_title) = existing_title_option {
-            existing_title.opening.attrs =
-                add_tag_attribute(tag_name.to_string(), existing_title.opening.attrs.clone());
+        match title_element_position {
+            Some(position) => {
+                let existing_title_element = match &mut jsx_element.children[position] {
+                    JSXElementChild::JSXElement(existing_title) => Some((**existing_title).take()),
+                    _ => None,
+                };
+                jsx_element.children[position] =
+                    JSXElementChild::JSXExprContainer(self.get_tag_element(existing_title_element));
+            }
+            None => jsx_element.children.insert(
+                0,
+                JSXElementChild::JSXExprContainer(self.get_tag_element(None)),
+            ),
         }
+    }
+
+    fn get_tag_element(&self, existing_title_option: Option<JSXElement>) -> JSXExprContainer {
+        let tag_name = &self.options.tag;
+
+        let existing_title_option = existing_title_option.map(|mut existing_title| {
+            add_tag_attribute(tag_name, &mut existing_title.opening.attrs);
+            existing_title
+        });
 
         let conditional_title = Box::new(Expr::Cond(CondExpr {
             span: DUMMY_SP,
@@ -52,10 +84,10 @@ impl DynamicTitleVisitor {
                         DUMMY_SP,
                     )))),
                 }],
-                if let Some(existing_title) = existing_title_option {
+                if let Some(existing_title) = &existing_title_option {
                     existing_title.opening.attrs.clone()
                 } else {
-                    vec![create_tag_id_attribute(tag_name.to_string())]
+                    vec![create_tag_id_attribute(tag_name)]
                 },
             )))),
             alt: Box::new(Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))),
@@ -76,7 +108,7 @@ impl DynamicTitleVisitor {
                             ))),
                             right: Box::new(Expr::Ident(Ident::new("undefined".into(), DUMMY_SP))),
                         })),
-                        cons: Box::new(Expr::JSXElement(Box::new(existing_title.clone()))),
+                        cons: Box::new(Expr::JSXElement(Box::new(existing_title))),
                         alt: conditional_title,
                     }))),
                 };
@@ -90,103 +122,64 @@ impl DynamicTitleVisitor {
     }
 }
 
+impl ElementPass for DynamicTitleVisitor {
+    fn enter_element(&mut self, _jsx_element: &mut JSXElement) {
+        self.depth += 1;
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        self.depth -= 1;
+        if self.depth == 0 && is_element_of(jsx_element, &self.elements) {
+            self.add_tag_element(jsx_element);
+        }
+    }
+}
+
 impl VisitMut for DynamicTitleVisitor {
     fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
-        if let JSXElement {
-            opening:
-                JSXOpeningElement {
-                    name: JSXElementName::Ident(Ident { sym, .. }),
-                    ..
-                },
-            ..
-        } = jsx_element
-        {
-            if self.elements.contains(&sym.to_string()) {
-                let mut existing_title_element: Option<JSXElement> = None;
-
-                if let Some(title_element_position) =
-                    jsx_element.children.iter().position(|child| {
-                        if let JSXElementChild::JSXElement(child_element) = child {
-                            if let JSXElement {
-                                opening:
-                                    JSXOpeningElement {
-                                        name: JSXElementName::Ident(Ident { sym, .. }),
-                                        ..
-                                    },
-                                ..
-                            } = *(child_element.clone())
-                            {
-                                if sym == self.options.tag {
-                                    existing_title_element = Some(*child_element.clone());
-                                    return true;
-                                }
-                            }
-                        }
-                        false
-                    })
-                {
-                    jsx_element.children[title_element_position] =
-                        JSXElementChild::JSXExprContainer(
-                            self.get_tag_element(&mut existing_title_element),
-                        );
-                } else {
-                    jsx_element.children.insert(
-                        0,
-                        JSXElementChild::JSXExprContainer(
-                            self.get_tag_element(&mut existing_title_element),
-                        ),
-                    )
-                }
-            }
+        if is_element_of(jsx_element, &self.elements) {
+            self.add_tag_element(jsx_element);
         }
     }
 }
 
-fn add_tag_attribute(tag: String, attributes: Vec<JSXAttrOrSpread>) -> Vec<JSXAttrOrSpread> {
-    let mut new_attributes = attributes.clone();
-    if let Some(id_position) = attributes.iter().position(|x| {
-        if let JSXAttrOrSpread::JSXAttr(JSXAttr {
-            name: JSXAttrName::Ident(Ident { sym, .. }),
-            ..
-        }) = x
+fn add_tag_attribute(tag: &str, attributes: &mut Vec<JSXAttrOrSpread>) {
+    let id_attribute = attributes.iter_mut().find_map(|x| match x {
+        JSXAttrOrSpread::JSXAttr(jsx_attr)
+            if matches!(&jsx_attr.name, JSXAttrName::Ident(Ident { sym, .. }) if sym == "id") =>
         {
-            if sym == "id" {
-                return true;
-            }
+            Some(jsx_attr)
         }
-        false
-    }) {
-        match &mut new_attributes[id_position] {
-            JSXAttrOrSpread::JSXAttr(jsx_attr) => {
-                let id_expr = Box::new(Expr::Ident(Ident::new(
-                    format!("{}Id", tag).into(),
-                    DUMMY_SP,
-                )));
-                jsx_attr.value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
-                    span: DUMMY_SP,
-                    expr: if let Some(JSXAttrValue::Lit(Lit::Str(existing_str_expr))) =
-                        jsx_attr.value.clone()
-                    {
-                        JSXExpr::Expr(Box::new(Expr::Bin(BinExpr {
-                            span: DUMMY_SP,
-                            op: BinaryOp::LogicalOr,
-                            left: id_expr,
-                            right: Box::new(Expr::Lit(Lit::Str(existing_str_expr))),
-                        })))
-                    } else {
-                        JSXExpr::Expr(id_expr)
-                    },
-                }))
-            }
-            JSXAttrOrSpread::SpreadElement(_) => {}
+        _ => None,
+    });
+
+    match id_attribute {
+        Some(jsx_attr) => {
+            let id_expr = Box::new(Expr::Ident(Ident::new(
+                format!("{}Id", tag).into(),
+                DUMMY_SP,
+            )));
+            jsx_attr.value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                span: DUMMY_SP,
+                expr: if let Some(JSXAttrValue::Lit(Lit::Str(existing_str_expr))) =
+                    jsx_attr.value.take()
+                {
+                    JSXExpr::Expr(Box::new(Expr::Bin(BinExpr {
+                        span: DUMMY_SP,
+                        op: BinaryOp::LogicalOr,
+                        left: id_expr,
+                        right: Box::new(Expr::Lit(Lit::Str(existing_str_expr))),
+                    })))
+                } else {
+                    JSXExpr::Expr(id_expr)
+                },
+            }))
         }
-    } else {
-        new_attributes.push(create_tag_id_attribute(tag));
+        None => attributes.push(create_tag_id_attribute(tag)),
     }
-    new_attributes
 }
 
-fn create_tag_id_attribute(tag: String) -> JSXAttrOrSpread {
+fn create_tag_id_attribute(tag: &str) -> JSXAttrOrSpread {
     JSXAttrOrSpread::JSXAttr(JSXAttr {
         span: DUMMY_SP,
         name: JSXAttrName::Ident(Ident::new("id".into(), DUMMY_SP)),
@@ -219,8 +212,8 @@ fn create_tag_element(
             name: JSXElementName::Ident(tag_ident),
         }),
         children: children
-            .iter()
-            .map(|x| JSXElementChild::JSXExprContainer(x.clone()))
+            .into_iter()
+            .map(JSXElementChild::JSXExprContainer)
             .collect(),
     }
 }
@@ -1,19 +1,21 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use swc_common::DUMMY_SP;
 use swc_core::ecma::{
     ast::*,
     visit::{VisitMut, VisitMutWith},
 };
 
-#[derive(Deserialize, Clone)]
+use crate::pipeline::{is_element_of, ElementPass};
+
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum NumberOrString {
     String(String),
     Number(f64),
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Options {
     width: Option<NumberOrString>,
     height: Option<NumberOrString>,
@@ -49,45 +51,53 @@ impl SVGEmDimensionsVisitor {
     }
 }
 
-impl VisitMut for SVGEmDimensionsVisitor {
-    fn visit_mut_jsx_opening_element(&mut self, jsx_opening_element: &mut JSXOpeningElement) {
-        jsx_opening_element.visit_mut_children_with(self);
-        if let JSXElementName::Ident(Ident { sym, .. }) = &jsx_opening_element.name {
-            if self.elements.contains(&sym.to_string()) {
-                let mut replace_width = false;
-                let mut replace_height = false;
-                for attr in jsx_opening_element.attrs.iter_mut() {
-                    if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
-                        if let JSXAttr {
-                            name: JSXAttrName::Ident(Ident { sym, .. }),
-                            ..
-                        } = jsx_attr
-                        {
-                            if sym == "height" || sym == "width" {
-                                jsx_attr.value = Some(if sym == "height" {
-                                    replace_height = true;
-                                    self.height_value.clone()
-                                } else {
-                                    replace_width = true;
-                                    self.width_value.clone()
-                                });
-                            }
-                        }
+impl ElementPass for SVGEmDimensionsVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        if !is_element_of(jsx_element, &self.elements) {
+            return;
+        }
+
+        let mut replace_width = false;
+        let mut replace_height = false;
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
+                if let JSXAttr {
+                    name: JSXAttrName::Ident(Ident { sym, .. }),
+                    ..
+                } = jsx_attr
+                {
+                    if sym == "height" || sym == "width" {
+                        jsx_attr.value = Some(if sym == "height" {
+                            replace_height = true;
+                            self.height_value.clone()
+                        } else {
+                            replace_width = true;
+                            self.width_value.clone()
+                        });
                     }
                 }
+            }
+        }
 
-                if !replace_width {
-                    jsx_opening_element.attrs.push(self.get_attr("width"));
-                }
+        if !replace_width {
+            let attr = self.get_attr("width");
+            jsx_element.opening.attrs.push(attr);
+        }
 
-                if !replace_height {
-                    jsx_opening_element.attrs.push(self.get_attr("height"));
-                }
-            }
+        if !replace_height {
+            let attr = self.get_attr("height");
+            jsx_element.opening.attrs.push(attr);
         }
     }
 }
 
+impl VisitMut for SVGEmDimensionsVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        jsx_element.visit_mut_children_with(self);
+        self.exit_element(jsx_element);
+    }
+}
+
 fn get_value(raw_option: &Option<NumberOrString>) -> JSXAttrValue {
     if let Some(raw) = raw_option {
         match raw {
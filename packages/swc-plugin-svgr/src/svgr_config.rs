@@ -0,0 +1,463 @@
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::{json, Map, Value};
+use std::fmt;
+use transform_svg_component::{
+    config::{default_named_export, default_state, State},
+    error::{parse_config, ConfigError},
+};
+
+pub use transform_svg_component::config::{ColorProps, ExpandProps, JSXRuntimeImport};
+
+use crate::{
+    cleanup::Options as CleanupOptions,
+    cleanup_numeric_values::Options as CleanupNumericValuesOptions,
+    collapse_groups::Options as CollapseGroupsOptions,
+    convert_shapes::Options as ConvertShapesOptions, css_variables::Options as CssVariablesOptions,
+    current_color::Options as CurrentColorOptions,
+    minify_path_data::Options as MinifyPathDataOptions, prefix_ids::Options as PrefixIdsOptions,
+    remove_unused_ids::Options as RemoveUnusedIdsOptions, Options,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Icon {
+    Enabled(bool),
+    Size(f64),
+    Value(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportType {
+    #[default]
+    Default,
+    Named,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsxRuntime {
+    Classic,
+    ClassicPreact,
+    Automatic,
+}
+
+/// The `@svgr/core` config, as SVGR passes it to its plugins.
+///
+/// `@svgr/core` fills in its defaults before calling plugins, so a key missing
+/// from a deserialized config behaves like `undefined` in JavaScript (e.g. no
+/// `dimensions` removes `width` and `height`). `Config::default()` holds the
+/// SVGR defaults instead.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    #[serde(default)]
+    pub typescript: bool,
+    #[serde(default)]
+    pub native: bool,
+    #[serde(rename = "ref", default)]
+    pub forward_ref: bool,
+    #[serde(default)]
+    pub memo: bool,
+    pub icon: Option<Icon>,
+    #[serde(default)]
+    pub dimensions: bool,
+    pub expand_props: Option<ExpandProps>,
+    #[serde(default)]
+    pub title_prop: bool,
+    #[serde(default)]
+    pub desc_prop: bool,
+    #[serde(default)]
+    pub export_type: ExportType,
+    #[serde(default = "default_named_export")]
+    pub named_export: String,
+    pub jsx_runtime: Option<JsxRuntime>,
+    /// Takes precedence over `jsx_runtime` when set.
+    pub jsx_runtime_import: Option<JSXRuntimeImport>,
+    /// Kept in the order of the config object, like `Object.keys`.
+    #[serde(default, with = "entries")]
+    pub svg_props: Vec<(String, String)>,
+    #[serde(default, with = "entries")]
+    pub replace_attr_values: Vec<(String, String)>,
+    /// Not an SVGR option: makes ids unique to each component instance with
+    /// React's `useId`.
+    #[serde(default)]
+    pub use_id: bool,
+    /// Not an SVGR option: turns the colors of the SVG into props.
+    pub color_props: Option<ColorProps>,
+    /// Not an SVGR option: `{}` enables every rule of the `cleanup` pass.
+    pub cleanup: Option<CleanupOptions>,
+    #[serde(default)]
+    pub inline_styles: bool,
+    #[serde(default)]
+    pub remove_hidden_elements: bool,
+    pub remove_unused_ids: Option<RemoveUnusedIdsOptions>,
+    pub prefix_ids: Option<PrefixIdsOptions>,
+    pub convert_shapes: Option<ConvertShapesOptions>,
+    /// Not an SVGR option either: `{}` minifies path data with 3 decimals.
+    pub minify_path_data: Option<MinifyPathDataOptions>,
+    pub cleanup_numeric_values: Option<CleanupNumericValuesOptions>,
+    pub collapse_groups: Option<CollapseGroupsOptions>,
+    pub current_color: Option<CurrentColorOptions>,
+    pub css_variables: Option<CssVariablesOptions>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            typescript: false,
+            native: false,
+            forward_ref: false,
+            memo: false,
+            icon: Some(Icon::Enabled(false)),
+            dimensions: true,
+            expand_props: Some(ExpandProps::End),
+            title_prop: false,
+            desc_prop: false,
+            export_type: ExportType::Default,
+            named_export: default_named_export(),
+            jsx_runtime: Some(JsxRuntime::Classic),
+            jsx_runtime_import: None,
+            svg_props: vec![],
+            replace_attr_values: vec![],
+            use_id: false,
+            color_props: None,
+            cleanup: None,
+            inline_styles: false,
+            remove_hidden_elements: false,
+            remove_unused_ids: None,
+            prefix_ids: None,
+            convert_shapes: None,
+            minify_path_data: None,
+            cleanup_numeric_values: None,
+            collapse_groups: None,
+            current_color: None,
+            css_variables: None,
+        }
+    }
+}
+
+/// Plugin options in the shape of an SVGR plugin call: `{ config, state }`.
+#[derive(Deserialize)]
+pub struct SvgrOptions {
+    pub config: Config,
+    #[serde(default = "default_state")]
+    pub state: State,
+    pub pipeline: Option<Vec<String>>,
+    /// Config sections of custom passes, passed through to `Options`.
+    #[serde(flatten)]
+    pub custom: Map<String, Value>,
+}
+
+impl SvgrOptions {
+    pub fn into_options(self) -> Result<Options, ConfigError> {
+        let mut options = self
+            .config
+            .to_options(self.state)
+            .map_err(|err| err.nested("config"))?;
+        options.pipeline = self.pipeline;
+        options.custom = self.custom;
+        Ok(options)
+    }
+}
+
+impl Config {
+    /// Builds the `swc-plugin-svgr` options for one component, following the
+    /// mapping of `@svgr/babel-preset`.
+    pub fn to_options(&self, state: State) -> Result<Options, ConfigError> {
+        let mut remove_attributes = vec!["version"];
+        let mut add_attributes: Vec<Value> = self
+            .svg_props
+            .iter()
+            .map(|(name, value)| {
+                let (value, literal) = get_attribute_value(value);
+                json!({ "name": name, "value": value, "literal": literal })
+            })
+            .collect();
+
+        if self.forward_ref {
+            add_attributes.push(json!({ "name": "ref", "value": "ref", "literal": true }));
+        }
+
+        if self.title_prop {
+            add_attributes
+                .push(json!({ "name": "aria-labelledby", "value": "titleId", "literal": true }));
+        }
+
+        if self.desc_prop {
+            add_attributes
+                .push(json!({ "name": "aria-describedby", "value": "descId", "literal": true }));
+        }
+
+        match self.expand_props {
+            None | Some(ExpandProps::Boolean(false)) => {}
+            Some(ExpandProps::Boolean(true)) => {
+                add_attributes.push(json!({ "name": "props", "spread": true }));
+            }
+            Some(ExpandProps::Start) => {
+                add_attributes
+                    .push(json!({ "name": "props", "spread": true, "position": "start" }));
+            }
+            Some(ExpandProps::End) => {
+                add_attributes.push(json!({ "name": "props", "spread": true, "position": "end" }));
+            }
+        }
+
+        if !self.dimensions {
+            remove_attributes.extend(["width", "height"]);
+        }
+
+        let em_dimensions = match (&self.icon, self.dimensions) {
+            (Some(Icon::Enabled(false)), _) | (_, false) => Value::Null,
+            (Some(Icon::Enabled(true)), _) if self.native => json!({ "width": 24, "height": 24 }),
+            (Some(Icon::Enabled(true)), _) | (None, _) => json!({}),
+            (Some(Icon::Size(size)), _) => json!({ "width": size, "height": size }),
+            (Some(Icon::Value(size)), _) => json!({ "width": size, "height": size }),
+        };
+
+        let replace_attribute_values = if self.replace_attr_values.is_empty() {
+            Value::Null
+        } else {
+            json!({
+                "values": self
+                    .replace_attr_values
+                    .iter()
+                    .map(|(value, new_value)| {
+                        let (new_value, literal) = get_attribute_value(new_value);
+                        json!({ "value": value, "newValue": new_value, "literal": literal })
+                    })
+                    .collect::<Vec<Value>>()
+            })
+        };
+
+        let mut transform_svg_component = json!({
+            "typescript": self.typescript,
+            "titleProp": self.title_prop,
+            "descProp": self.desc_prop,
+            "ref": self.forward_ref,
+            "state": state,
+            "native": self.native,
+            "memo": self.memo,
+            "exportType": self.export_type,
+            "namedExport": self.named_export,
+            "useId": self.use_id,
+            "colorProps": self.color_props,
+        });
+        if let Some(expand_props) = &self.expand_props {
+            extend(
+                &mut transform_svg_component,
+                json!({ "expandProps": expand_props }),
+            );
+        }
+        extend(&mut transform_svg_component, self.jsx_runtime_options());
+
+        let options = json!({
+            "transform_svg_component": transform_svg_component,
+            "cleanup": self.cleanup,
+            "inline_styles": self.inline_styles,
+            "remove_hidden_elements": self.remove_hidden_elements,
+            "remove_unused_ids": self.remove_unused_ids,
+            "prefix_ids": self.prefix_ids,
+            "convert_shapes": self.convert_shapes,
+            "minify_path_data": self.minify_path_data,
+            "cleanup_numeric_values": self.cleanup_numeric_values,
+            "collapse_groups": self.collapse_groups,
+            "em_dimensions": em_dimensions,
+            "remove_jsx_attribute": { "elements": ["svg", "Svg"], "attributes": remove_attributes },
+            "add_jsx_attribute": { "elements": ["svg", "Svg"], "attributes": add_attributes },
+            "replace_attribute_values": replace_attribute_values,
+            "current_color": self.current_color,
+            "css_variables": self.css_variables,
+            "title_prop": self.title_prop,
+            "desc_prop": self.desc_prop,
+            "native": self.native,
+        });
+
+        parse_config(&options.to_string())
+    }
+
+    fn jsx_runtime_options(&self) -> Value {
+        if let Some(jsx_runtime_import) = &self.jsx_runtime_import {
+            return json!({
+                "importSource": jsx_runtime_import.source,
+                "jsxRuntimeImport": jsx_runtime_import,
+            });
+        }
+
+        match self.jsx_runtime.unwrap_or(JsxRuntime::Classic) {
+            JsxRuntime::Classic => json!({
+                "jsxRuntime": "classic",
+                "importSource": "react",
+                "jsxRuntimeImport": { "namespace": "React", "source": "react" },
+            }),
+            JsxRuntime::ClassicPreact => json!({
+                "jsxRuntime": "classic",
+                "importSource": "preact/compat",
+                "jsxRuntimeImport": { "specifiers": ["h"], "source": "preact" },
+            }),
+            JsxRuntime::Automatic => json!({ "jsxRuntime": "automatic" }),
+        }
+    }
+}
+
+/// Values wrapped in braces, such as `{props.color}`, are JavaScript expressions.
+fn get_attribute_value(value: &str) -> (&str, bool) {
+    if value.len() >= 2 && value.starts_with('{') && value.ends_with('}') {
+        (&value[1..value.len() - 1], true)
+    } else {
+        (value, false)
+    }
+}
+
+fn extend(target: &mut Value, source: Value) {
+    if let (Value::Object(target), Value::Object(source)) = (target, source) {
+        target.extend::<Map<String, Value>>(source);
+    }
+}
+
+/// (De)serializes `(key, value)` pairs as an object, keeping the key order of
+/// the source, which `BTreeMap` would lose. `null` gives no entries.
+mod entries {
+    use super::*;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(
+        entries: &[(String, String)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(entries.iter().map(|(key, value)| (key, value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(String, String)>, D::Error> {
+        deserializer.deserialize_any(EntriesVisitor)
+    }
+
+    struct EntriesVisitor;
+
+    impl<'de> Visitor<'de> for EntriesVisitor {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an object with string values")
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(vec![])
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = vec![];
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(svgr_options: &str) -> Options {
+        crate::parse_options(svgr_options).unwrap()
+    }
+
+    fn to_value<T: Serialize>(value: &T) -> Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn treat_missing_keys_as_undefined() {
+        let options = options(r#"{ "config": {}, "state": { "componentName": "SvgComponent" } }"#);
+
+        assert!(options.em_dimensions.is_none());
+        assert_eq!(
+            to_value(&options.remove_jsx_attribute)["attributes"],
+            json!(["version", "width", "height"])
+        );
+        assert_eq!(
+            to_value(&options.add_jsx_attribute)["attributes"],
+            json!([])
+        );
+        let transform_svg_component = to_value(&options.transform_svg_component);
+        assert_eq!(transform_svg_component["expandProps"], json!("end"));
+        assert_eq!(
+            transform_svg_component["state"]["componentName"],
+            json!("SvgComponent")
+        );
+    }
+
+    #[test]
+    fn map_svgr_config() {
+        // Parsed from source text: `json!` would sort the `svgProps` keys.
+        let options = options(
+            r##"{
+                "config": {
+                    "dimensions": true,
+                    "icon": "2em",
+                    "expandProps": true,
+                    "svgProps": { "role": "img", "focusable": "{false}" },
+                    "replaceAttrValues": { "#063855": "{props.color}" },
+                    "jsxRuntimeImport": { "source": "preact", "specifiers": ["h"] },
+                    "typescript": true,
+                    "plugins": ["@svgr/plugin-svgo", "svgr-plugin-swc"]
+                },
+                "pipeline": ["transform_svg_component", "transform_attribute"],
+                "my_pass": { "enabled": true }
+            }"##,
+        );
+
+        assert_eq!(
+            to_value(&options.em_dimensions),
+            json!({ "width": "2em", "height": "2em" })
+        );
+        assert_eq!(
+            to_value(&options.add_jsx_attribute)["attributes"],
+            json!([
+                { "name": "role", "value": "img", "literal": false, "spread": null, "position": null },
+                { "name": "focusable", "value": "false", "literal": true, "spread": null, "position": null },
+                { "name": "props", "value": null, "literal": null, "spread": true, "position": null },
+            ])
+        );
+        assert_eq!(
+            to_value(&options.replace_attribute_values)["values"][0]["newValue"],
+            json!("props.color")
+        );
+        let transform_svg_component = to_value(&options.transform_svg_component);
+        assert_eq!(transform_svg_component["expandProps"], json!(true));
+        assert_eq!(transform_svg_component["importSource"], json!("preact"));
+        assert_eq!(transform_svg_component["typescript"], json!(true));
+        assert_eq!(
+            options.pipeline,
+            Some(vec![
+                "transform_svg_component".to_string(),
+                "transform_attribute".to_string()
+            ])
+        );
+        assert_eq!(options.custom["my_pass"], json!({ "enabled": true }));
+        assert!(options.cleanup.is_none());
+    }
+
+    #[test]
+    fn enable_cleanup_rules_by_default() {
+        let options = options(r#"{ "config": { "cleanup": { "metadata": false } } }"#);
+
+        let cleanup = options.cleanup.unwrap();
+        assert!(!cleanup.metadata);
+        assert!(cleanup.editor_namespaces && cleanup.unused_namespaces);
+    }
+
+    #[test]
+    fn report_invalid_svgr_config() {
+        let err = crate::parse_options(r#"{ "config": { "jsxRuntime": "solid" } }"#)
+            .err()
+            .unwrap();
+        assert_eq!(err.path, "config.jsxRuntime");
+    }
+}
@@ -1,50 +1,71 @@
-use regex::Regex;
 use swc_common::DUMMY_SP;
-use swc_core::ecma::{ast::*, visit::VisitMut};
+use swc_core::ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+};
+
+use transform_svg_component::use_id::template_element;
+
+use crate::pipeline::ElementPass;
 
 pub struct TransformAttributeVisitor;
 
-impl VisitMut for TransformAttributeVisitor {
-    fn visit_mut_jsx_attr(&mut self, jsx_attr: &mut JSXAttr) {
-        let original_name = jsx_attr.name.clone();
-        match original_name {
+impl TransformAttributeVisitor {
+    fn transform_attr(&self, jsx_attr: &mut JSXAttr) {
+        let new_name = match &jsx_attr.name {
             JSXAttrName::JSXNamespacedName(JSXNamespacedName {
                 ns: Ident { sym: ns, .. },
                 name: Ident { sym: name, .. },
                 ..
-            }) => {
-                jsx_attr.name =
-                    JSXAttrName::Ident(Ident::new(namespace_to_camel(&ns, &name).into(), DUMMY_SP));
-            }
+            }) => namespace_to_camel(ns, name),
             JSXAttrName::Ident(Ident { sym, .. }) => {
-                if sym.eq("class") {
-                    jsx_attr.name = JSXAttrName::Ident(Ident::new("className".into(), DUMMY_SP));
+                if sym == "class" {
+                    "className".to_string()
+                } else if sym == "style" {
+                    let style = match &jsx_attr.value {
+                        Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) => css_to_obj(value),
+                        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                            expr: JSXExpr::Expr(expr),
+                            ..
+                        })) => match &**expr {
+                            Expr::Tpl(tpl) => tpl_to_obj(tpl),
+                            _ => return,
+                        },
+                        _ => return,
+                    };
+                    jsx_attr.value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        span: DUMMY_SP,
+                        expr: JSXExpr::Expr(Box::new(Expr::Object(style))),
+                    }));
                     return;
-                }
-
-                if sym.eq("style") {
-                    if let Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))) =
-                        jsx_attr.value.clone()
-                    {
-                        jsx_attr.value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
-                            span: DUMMY_SP,
-                            expr: JSXExpr::Expr(Box::new(Expr::Object(css_to_obj(&value)))),
-                        }));
-                    }
+                } else if sym.starts_with("data-") || sym.starts_with("aria-") {
                     return;
-                }
-
-                let re = Regex::new(r"^data-|^aria-").unwrap();
-
-                if re.is_match(&sym) {
-                    jsx_attr.name =
-                        JSXAttrName::Ident(Ident::new(format!("{}", sym).into(), DUMMY_SP));
+                } else if sym.contains('-') {
+                    hyphen_to_camel(sym)
                 } else {
-                    jsx_attr.name =
-                        JSXAttrName::Ident(Ident::new(hyphen_to_camel(&sym).into(), DUMMY_SP));
+                    return;
                 }
             }
         };
+
+        jsx_attr.name = JSXAttrName::Ident(Ident::new(new_name.into(), DUMMY_SP));
+    }
+}
+
+impl ElementPass for TransformAttributeVisitor {
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        for attr in jsx_element.opening.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
+                self.transform_attr(jsx_attr);
+            }
+        }
+    }
+}
+
+impl VisitMut for TransformAttributeVisitor {
+    fn visit_mut_jsx_attr(&mut self, jsx_attr: &mut JSXAttr) {
+        jsx_attr.visit_mut_children_with(self);
+        self.transform_attr(jsx_attr);
     }
 }
 
@@ -110,6 +131,82 @@ fn css_to_obj(css: &str) -> ObjectLit {
     }
 }
 
+/// Like `css_to_obj`, for a style given as a template literal, as the ids of
+/// the `useId` mode of `transform_svg_component` give. Declarations holding
+/// expressions get template literal values.
+fn tpl_to_obj(tpl: &Tpl) -> ObjectLit {
+    // The text of each declaration, split around its expressions.
+    let mut declarations: Vec<(Vec<String>, Vec<Box<Expr>>)> = vec![(vec![String::new()], vec![])];
+    for (index, quasi) in tpl.quasis.iter().enumerate() {
+        let text = quasi.cooked.as_deref().unwrap_or(&quasi.raw);
+        for (part_index, part) in text.split(';').enumerate() {
+            if part_index > 0 {
+                declarations.push((vec![String::new()], vec![]));
+            }
+            let (texts, _) = declarations.last_mut().unwrap();
+            texts.last_mut().unwrap().push_str(part);
+        }
+        if let Some(expr) = tpl.exprs.get(index) {
+            let (texts, exprs) = declarations.last_mut().unwrap();
+            texts.push(String::new());
+            exprs.push(expr.clone());
+        }
+    }
+
+    let props = declarations
+        .into_iter()
+        .filter_map(|(mut texts, exprs)| {
+            let (key, value) = texts[0].split_once(':')?;
+            let key = key.trim().to_string();
+            texts[0] = value.trim_start().to_string();
+            let last = texts.len() - 1;
+            texts[last] = texts[last].trim_end().to_string();
+            if key.is_empty() {
+                return None;
+            }
+
+            let value = if exprs.len() == 1 && texts.iter().all(String::is_empty) {
+                // `fill: ${color}`.
+                *exprs.into_iter().next().unwrap()
+            } else if exprs.is_empty() {
+                Expr::Lit(if is_numeric(&texts[0]) {
+                    Lit::Num(Number {
+                        span: DUMMY_SP,
+                        value: texts[0].parse::<f64>().unwrap(),
+                        raw: None,
+                    })
+                } else {
+                    Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: texts[0].clone().into(),
+                        raw: None,
+                    })
+                })
+            } else {
+                Expr::Tpl(Tpl {
+                    span: DUMMY_SP,
+                    quasis: texts
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, text)| template_element(text, index == last))
+                        .collect(),
+                    exprs,
+                })
+            };
+
+            Some(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(Ident::new(hyphen_to_camel(&key).into(), DUMMY_SP)),
+                value: Box::new(value),
+            }))))
+        })
+        .collect();
+
+    ObjectLit {
+        span: DUMMY_SP,
+        props,
+    }
+}
+
 pub fn is_numeric(value: &str) -> bool {
     value.parse::<f64>().is_ok()
 }
@@ -171,6 +268,20 @@ mod tests {
         }}/>;"#
     );
 
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(TransformAttributeVisitor),
+        style_attribute_template,
+        r#"<path style={`fill: url(#${id}-a); stroke-width: 2;`} />"#,
+        r#"<path style={{
+            fill: `url(#${id}-a)`,
+            strokeWidth: 2,
+        }}/>;"#
+    );
+
     test!(
         Syntax::Es(EsConfig {
             jsx: true,
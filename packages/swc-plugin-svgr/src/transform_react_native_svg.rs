@@ -1,7 +1,7 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap},
     rc::Rc,
 };
 use swc_common::{
@@ -16,17 +16,19 @@ use swc_core::{
     plugin::proxies::PluginCommentsProxy,
 };
 
+use crate::pipeline::ElementPass;
+
 pub struct State {
-    replaced_components: HashSet<String>,
-    unsupported_components: HashSet<String>,
+    replaced_components: BTreeSet<String>,
+    unsupported_components: BTreeSet<String>,
     element_to_components: HashMap<String, String>,
 }
 
 impl Default for State {
     fn default() -> Self {
         State {
-            replaced_components: HashSet::new(),
-            unsupported_components: HashSet::new(),
+            replaced_components: BTreeSet::new(),
+            unsupported_components: BTreeSet::new(),
             element_to_components: [
                 ("svg", "Svg"),
                 ("circle", "Circle"),
@@ -73,99 +75,116 @@ impl<C: Comments> ImportDeclVisitor<C> {
 pub struct JSXElementVisitor {
     state: Rc<RefCell<State>>,
     is_in_svg_element: bool,
+    /// Number of `svg` elements entered by the pipeline and not exited yet.
+    svg_depth: usize,
 }
 
 impl JSXElementVisitor {
     pub fn new(state: Rc<RefCell<State>>) -> Self {
         JSXElementVisitor {
             is_in_svg_element: false,
+            svg_depth: 0,
             state,
         }
     }
-}
 
-impl VisitMut for JSXElementVisitor {
-    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
-        let old_is_in_svg_element = self.is_in_svg_element;
-        if let JSXElement {
-            opening:
-                JSXOpeningElement {
-                    name: JSXElementName::Ident(Ident { sym, .. }),
+    fn remove_unsupported_children(&self, jsx_element: &mut JSXElement) {
+        let mut state = self.state.borrow_mut();
+        jsx_element.children.retain(|x| {
+            if let JSXElementChild::JSXElement(child_element) = x {
+                if let JSXElementName::Ident(Ident {
+                    sym: child_element_name,
                     ..
-                },
-            ..
-        } = jsx_element
-        {
-            if sym == "svg" {
-                self.is_in_svg_element = true;
-            }
-
-            if self.is_in_svg_element {
-                jsx_element.children.retain(|x| {
-                    if let JSXElementChild::JSXElement(child_element) = x {
-                        let mut state = self.state.borrow_mut();
-                        if let JSXElement {
-                            opening:
-                                JSXOpeningElement {
-                                    name:
-                                        JSXElementName::Ident(Ident {
-                                            sym: child_element_name,
-                                            ..
-                                        }),
-                                    ..
-                                },
-                            ..
-                        } = *(child_element.clone())
-                        {
-                            if !state
-                                .element_to_components
-                                .contains_key(&child_element_name.to_string())
-                            {
-                                state
-                                    .unsupported_components
-                                    .insert(child_element_name.to_string());
-                                return false;
-                            }
-                        }
+                }) = &child_element.opening.name
+                {
+                    if !state.element_to_components.contains_key(&**child_element_name) {
+                        state
+                            .unsupported_components
+                            .insert(child_element_name.to_string());
+                        return false;
                     }
-                    true
-                });
+                }
+            }
+            true
+        });
+    }
 
-                let component = {
-                    let state = self.state.borrow();
-                    state.element_to_components.get(&sym.to_string()).cloned()
-                };
+    fn replace_component(&self, jsx_element: &mut JSXElement) {
+        let component = match &jsx_element.opening.name {
+            JSXElementName::Ident(Ident { sym, .. }) => {
+                self.state.borrow().element_to_components.get(&**sym).cloned()
+            }
+            _ => None,
+        };
 
-                if let Some(component) = component {
-                    let new_name =
-                        JSXElementName::Ident(Ident::new(component.as_str().into(), DUMMY_SP));
-                    jsx_element.opening.name = new_name.clone();
-                    if let Some(closing_element) = &mut jsx_element.closing {
-                        if let JSXElementName::Ident(Ident { .. }) = &mut closing_element.name {
-                            closing_element.name = new_name;
-                        }
-                    }
-                    self.state
-                        .borrow_mut()
-                        .replaced_components
-                        .insert(component);
+        if let Some(component) = component {
+            let new_name = JSXElementName::Ident(Ident::new(component.as_str().into(), DUMMY_SP));
+            jsx_element.opening.name = new_name.clone();
+            if let Some(closing_element) = &mut jsx_element.closing {
+                if let JSXElementName::Ident(Ident { .. }) = &mut closing_element.name {
+                    closing_element.name = new_name;
                 }
             }
+            self.state
+                .borrow_mut()
+                .replaced_components
+                .insert(component);
         }
+    }
+}
 
-        jsx_element.visit_mut_children_with(self);
+fn is_svg(jsx_element: &JSXElement) -> bool {
+    matches!(&jsx_element.opening.name, JSXElementName::Ident(Ident { sym, .. }) if sym == "svg")
+}
+
+/// Unsupported elements are dropped before their descendants are visited, so
+/// the transform walks the tree top-down. In a pipeline, it waits for the
+/// outermost `svg` element to be exited, i.e. for the other passes to finish
+/// its whole subtree, then walks it.
+impl ElementPass for JSXElementVisitor {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        if is_svg(jsx_element) {
+            self.svg_depth += 1;
+        }
+    }
 
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        if is_svg(jsx_element) {
+            self.svg_depth -= 1;
+            if self.svg_depth == 0 {
+                self.visit_mut_jsx_element(jsx_element);
+            }
+        }
+    }
+}
+
+impl VisitMut for JSXElementVisitor {
+    fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
+        let old_is_in_svg_element = self.is_in_svg_element;
+        if is_svg(jsx_element) {
+            self.is_in_svg_element = true;
+        }
+
+        if self.is_in_svg_element && matches!(jsx_element.opening.name, JSXElementName::Ident(_)) {
+            self.remove_unsupported_children(jsx_element);
+            self.replace_component(jsx_element);
+        }
+
+        jsx_element.visit_mut_children_with(self);
         self.is_in_svg_element = old_is_in_svg_element;
     }
 }
 
 impl<C: Comments> VisitMut for ImportDeclVisitor<C> {
+    /// Imports only appear at the top level, so statements are not visited.
+    fn visit_mut_module_item(&mut self, module_item: &mut ModuleItem) {
+        if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = module_item {
+            self.visit_mut_import_decl(import_decl);
+        }
+    }
+
     fn visit_mut_import_decl(&mut self, import_decl: &mut ImportDecl) {
-        let Str {
-            value: source_value,
-            ..
-        } = *(import_decl.src.clone());
-        if source_value.to_string() == "react-native-svg" {
+        if &*import_decl.src.value == "react-native-svg" {
             for component in &self.state.borrow().replaced_components {
                 if import_decl.specifiers.iter().any(|x| match x {
                     ImportSpecifier::Default(ImportDefaultSpecifier {
@@ -227,11 +246,42 @@ pub struct TransformReactNativeSVGVisitor {
     pub comments: Option<PluginCommentsProxy>,
 }
 
+impl TransformReactNativeSVGVisitor {
+    /// Turns the transform into an element pass, which completes the
+    /// `react-native-svg` import once every element went through it.
+    pub fn into_pass(self) -> TransformReactNativeSVGPass {
+        let state: Rc<RefCell<State>> = Rc::new(RefCell::new(State::default()));
+        TransformReactNativeSVGPass {
+            element_visitor: JSXElementVisitor::new(state.clone()),
+            import_decl_visitor: ImportDeclVisitor::new(state, self.comments),
+        }
+    }
+}
+
 impl VisitMut for TransformReactNativeSVGVisitor {
     fn visit_mut_program(&mut self, program: &mut Program) {
-        let state: Rc<RefCell<State>> = Rc::new(RefCell::new(State::default()));
-        program.visit_mut_with(&mut (JSXElementVisitor::new(state.clone())));
-        program.visit_mut_with(&mut (ImportDeclVisitor::new(state, self.comments)));
+        let mut pass = TransformReactNativeSVGVisitor { comments: self.comments }.into_pass();
+        program.visit_mut_with(&mut pass.element_visitor);
+        pass.finish(program);
+    }
+}
+
+pub struct TransformReactNativeSVGPass {
+    element_visitor: JSXElementVisitor,
+    import_decl_visitor: ImportDeclVisitor<Option<PluginCommentsProxy>>,
+}
+
+impl ElementPass for TransformReactNativeSVGPass {
+    fn enter_element(&mut self, jsx_element: &mut JSXElement) {
+        self.element_visitor.enter_element(jsx_element);
+    }
+
+    fn exit_element(&mut self, jsx_element: &mut JSXElement) {
+        self.element_visitor.exit_element(jsx_element);
+    }
+
+    fn finish(&mut self, program: &mut Program) {
+        program.visit_mut_with(&mut self.import_decl_visitor);
     }
 }
 
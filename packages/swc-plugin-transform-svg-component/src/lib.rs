@@ -3,21 +3,46 @@
 use swc_core::{
     ecma::{
         ast::*,
-        visit::{as_folder, FoldWith},
+        visit::VisitMutWith,
     },
-    plugin::{plugin_transform, proxies::TransformPluginProgramMetadata},
+    plugin::{
+        metadata::TransformPluginMetadataContextKind, plugin_transform,
+        proxies::TransformPluginProgramMetadata,
+    },
+};
+pub use transform_svg_component::{
+    config::Config,
+    error::{parse_config, ConfigError},
+    TransformSVGComponentVisitor,
 };
-pub use transform_svg_component::{ config::Config, TransformSVGComponentVisitor};
 
 
 #[plugin_transform]
-pub fn svg_to_component(program: Program, data: TransformPluginProgramMetadata) -> Program {
-    let config = serde_json::from_str::<Config>(
-        &data
-            .get_transform_plugin_config()
-            .expect("failed to get plugin config for transform-svg-component"),
-    )
-    .expect("invalid config for transform-svg-component");
+pub fn svg_to_component(mut program: Program, data: TransformPluginProgramMetadata) -> Program {
+    let mut config = match data.get_transform_plugin_config() {
+        Some(config) => match parse_config::<Config>(&config) {
+            Ok(config) => config,
+            Err(err) => {
+                err.emit();
+                return program;
+            }
+        },
+        None => {
+            ConfigError::new("", "failed to get plugin config for transform-svg-component").emit();
+            return program;
+        }
+    };
+
+    config.resolve_component_name(
+        data.get_context(&TransformPluginMetadataContextKind::Filename)
+            .as_deref(),
+    );
+
+    let mut visitor = TransformSVGComponentVisitor::new(config);
+    program.visit_mut_with(&mut visitor);
+    for err in visitor.errors {
+        err.emit();
+    }
 
-    program.fold_with(&mut as_folder(TransformSVGComponentVisitor::new(config)))
+    program
 }
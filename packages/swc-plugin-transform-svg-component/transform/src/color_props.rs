@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{ast::*, utils::is_valid_ident},
+};
+
+use crate::{config::ColorProps, error::ConfigError, use_id::template_element};
+
+/// Attributes and style properties holding colors, lowercased without `-`.
+const COLOR_PROPERTIES: [&str; 6] = [
+    "fill",
+    "stroke",
+    "stopcolor",
+    "floodcolor",
+    "lightingcolor",
+    "color",
+];
+
+/// Paint values that are not colors, compared in lowercase.
+const NON_COLORS: [&str; 8] = [
+    "none",
+    "transparent",
+    "currentcolor",
+    "inherit",
+    "initial",
+    "unset",
+    "context-fill",
+    "context-stroke",
+];
+
+/// Bindings of the component function a color prop must not shadow: the
+/// rest of the props, the title and description props, the `useId()` result
+/// and the React import the classic JSX runtime refers to.
+const RESERVED_NAMES: [&str; 7] = ["props", "title", "titleId", "desc", "descId", "id", "React"];
+
+/// A color prop: its name, and the color it defaults to.
+pub struct ColorProp {
+    pub name: String,
+    pub default: String,
+}
+
+/// `color` in lowercase, with short hex colors expanded: `#FFF` gives
+/// `#ffffff`.
+pub fn normalize_color(color: &str) -> String {
+    let color = color.trim().to_ascii_lowercase();
+    match color.strip_prefix('#') {
+        Some(hex) if hex.len() == 3 || hex.len() == 4 => {
+            let mut expanded = String::from("#");
+            for c in hex.chars() {
+                expanded.push(c);
+                expanded.push(c);
+            }
+            expanded
+        }
+        _ => color,
+    }
+}
+
+/// Whether the attribute or style property `name`, lowercased without `-`,
+/// holds a color.
+pub fn is_color_property(name: &str) -> bool {
+    COLOR_PROPERTIES.contains(&name)
+}
+
+/// Whether a paint value is a color, rather than `none`, a keyword, a
+/// `url()` reference or a CSS variable.
+pub fn is_color(value: &str) -> bool {
+    let value = value.trim().to_ascii_lowercase();
+    !value.is_empty()
+        && !NON_COLORS.contains(&value.as_str())
+        && !value.contains("url(")
+        && !value.starts_with("var(")
+}
+
+/// Replaces the colors of `jsx_element` and its descendants, in attributes
+/// and style strings, with props named after `config`: `fill="#063855"`
+/// becomes `fill={primaryColor}`.
+///
+/// Returns the props, from the most to the least used color, ties going to
+/// the color found first, along with the names of `config` that are not
+/// identifiers or clash with the component's bindings. Those are skipped, so
+/// their colors get the next name instead.
+pub fn extract_colors(
+    jsx_element: &mut JSXElement,
+    config: &ColorProps,
+) -> (Vec<ColorProp>, Vec<ConfigError>) {
+    let mut errors = vec![];
+    // Normalized colors with their first spelling and count, in document order.
+    let mut colors: Vec<(String, String, usize)> = vec![];
+    for_each_color(jsx_element, &mut |color| {
+        let normalized = normalize_color(color);
+        match colors.iter_mut().find(|(other, _, _)| *other == normalized) {
+            Some((_, _, count)) => *count += 1,
+            None => colors.push((normalized, color.trim().to_string(), 1)),
+        }
+    });
+    // Stable, so ties keep the document order.
+    colors.sort_by(|(_, _, a), (_, _, b)| b.cmp(a));
+
+    // Sorted, so errors come in a stable order.
+    let mut mapping = config.colors.iter().collect::<Vec<_>>();
+    mapping.sort();
+    let mut explicit = HashMap::new();
+    for (color, name) in mapping {
+        match validate_name(name, &format!("colors.{}", color)) {
+            Ok(()) => {
+                explicit.insert(normalize_color(color), name.clone());
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+    let mut valid_names = vec![];
+    for (index, name) in config.names.iter().enumerate() {
+        match validate_name(name, &format!("names[{}]", index)) {
+            Ok(()) => valid_names.push(name),
+            Err(err) => errors.push(err),
+        }
+    }
+    let mut available = valid_names
+        .into_iter()
+        .filter(|name| !explicit.values().any(|explicit| explicit == *name));
+    let mut props: Vec<ColorProp> = vec![];
+    let mut names = HashMap::new();
+    for (index, (normalized, color, _)) in colors.into_iter().enumerate() {
+        let name = match explicit.get(&normalized) {
+            Some(name) => name.clone(),
+            None => match available.next() {
+                Some(name) => name.clone(),
+                None => {
+                    let mut number = index + 1;
+                    while props
+                        .iter()
+                        .any(|prop| prop.name == format!("color{}", number))
+                        || explicit
+                            .values()
+                            .any(|name| *name == format!("color{}", number))
+                    {
+                        number += 1;
+                    }
+                    format!("color{}", number)
+                }
+            },
+        };
+        names.insert(normalized, name.clone());
+        // Two colors mapped to the same name share the prop.
+        if !props.iter().any(|prop| prop.name == name) {
+            props.push(ColorProp {
+                name,
+                default: color,
+            });
+        }
+    }
+
+    if !props.is_empty() {
+        rewrite(jsx_element, &names);
+    }
+    (props, errors)
+}
+
+/// Checks that `name`, found at `path` in the `colorProps` config, can name a
+/// prop.
+fn validate_name(name: &str, path: &str) -> Result<(), ConfigError> {
+    let err = |message: &str| {
+        ConfigError::new(format!("colorProps.{}", path), message)
+            .with_value(format!("\"{}\"", name))
+    };
+    if !is_valid_ident(&name.into()) {
+        Err(err("color prop name is not a JavaScript identifier")
+            .with_hint("use a name like \"brandColor\"; the color gets the next name instead"))
+    } else if RESERVED_NAMES.contains(&name) {
+        Err(
+            err("color prop name clashes with a binding of the component").with_hint(format!(
+                "avoid {}; the color gets the next name instead",
+                RESERVED_NAMES
+                    .map(|name| format!("\"{}\"", name))
+                    .join(", ")
+            )),
+        )
+    } else {
+        Ok(())
+    }
+}
+
+/// Calls `f` with every color of `jsx_element` and its descendants.
+fn for_each_color(jsx_element: &JSXElement, f: &mut impl FnMut(&str)) {
+    for attr in &jsx_element.opening.attrs {
+        if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+            ..
+        }) = attr
+        {
+            let name = normalize(sym);
+            if is_color_property(&name) && is_color(value) {
+                f(value);
+            } else if name == "style" {
+                for (_, value) in style_colors(value) {
+                    f(value);
+                }
+            }
+        }
+    }
+
+    for child in &jsx_element.children {
+        if let JSXElementChild::JSXElement(child) = child {
+            for_each_color(child, f);
+        }
+    }
+}
+
+fn rewrite(jsx_element: &mut JSXElement, names: &HashMap<String, String>) {
+    let prop = |name: &str| Box::new(Expr::Ident(Ident::new(name.into(), DUMMY_SP)));
+
+    for attr in jsx_element.opening.attrs.iter_mut() {
+        let JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            value,
+            ..
+        }) = attr
+        else {
+            continue;
+        };
+        let Some(JSXAttrValue::Lit(Lit::Str(Str { value: string, .. }))) = value else {
+            continue;
+        };
+        let name = normalize(sym);
+
+        let expr = if is_color_property(&name) && is_color(string) {
+            names.get(&normalize_color(string)).map(|name| prop(name))
+        } else if name == "style" {
+            // A template literal, which `transform_attribute` turns into an
+            // object like the other styles.
+            let spans = style_colors(string);
+            if spans.is_empty() {
+                continue;
+            }
+            let mut quasis = vec![];
+            let mut exprs = vec![];
+            let mut end = 0;
+            for (start, color) in spans {
+                let Some(name) = names.get(&normalize_color(color)) else {
+                    continue;
+                };
+                quasis.push(template_element(string[end..start].to_string(), false));
+                exprs.push(prop(name));
+                end = start + color.len();
+            }
+            quasis.push(template_element(string[end..].to_string(), true));
+            Some(Box::new(Expr::Tpl(Tpl {
+                span: DUMMY_SP,
+                exprs,
+                quasis,
+            })))
+        } else {
+            None
+        };
+
+        if let Some(expr) = expr {
+            *value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                span: DUMMY_SP,
+                expr: JSXExpr::Expr(expr),
+            }));
+        }
+    }
+
+    for child in jsx_element.children.iter_mut() {
+        if let JSXElementChild::JSXElement(child) = child {
+            rewrite(child, names);
+        }
+    }
+}
+
+/// The colors of the declarations of a style string, with their offsets.
+fn style_colors(css: &str) -> Vec<(usize, &str)> {
+    let mut colors = vec![];
+    let mut offset = 0;
+    for declaration in css.split(';') {
+        if let Some((property, value)) = declaration.split_once(':') {
+            let trimmed = value.trim();
+            if is_color_property(&normalize(property)) && is_color(trimmed) {
+                let start = offset + property.len() + 1 + value.len() - value.trim_start().len();
+                colors.push((start, trimmed));
+            }
+        }
+        offset += declaration.len() + 1;
+    }
+    colors
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::{
+        common::{input::StringInput, BytePos},
+        ecma::parser::{EsConfig, Parser, Syntax},
+    };
+
+    /// The names and defaults of the color props of `source`, and the config
+    /// errors found extracting them.
+    fn extract(source: &str, config: &ColorProps) -> (Vec<(String, String)>, Vec<ConfigError>) {
+        let mut parser = Parser::new(
+            Syntax::Es(EsConfig {
+                jsx: true,
+                ..Default::default()
+            }),
+            StringInput::new(source, BytePos(0), BytePos(0)),
+            None,
+        );
+        let Expr::JSXElement(mut jsx_element) = *parser.parse_expr().unwrap() else {
+            panic!("{} is not a JSX element", source);
+        };
+        let (props, errors) = extract_colors(&mut jsx_element, config);
+        let props = props
+            .into_iter()
+            .map(|prop| (prop.name, prop.default))
+            .collect();
+        (props, errors)
+    }
+
+    fn props(props: &[(&str, &str)]) -> Vec<(String, String)> {
+        props
+            .iter()
+            .map(|(name, default)| (name.to_string(), default.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn name_colors_from_most_to_least_used() {
+        let (colors, errors) = extract(
+            r##"<svg><path fill="red" stroke="#063855" /><path fill="#FFF" style="stop-color: #063855" /><path fill="#ffffff" stroke="none" /></svg>"##,
+            &ColorProps::default(),
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            colors,
+            props(&[
+                ("primaryColor", "#063855"),
+                ("secondaryColor", "#FFF"),
+                ("tertiaryColor", "red"),
+            ])
+        );
+    }
+
+    #[test]
+    fn name_mapped_colors_explicitly() {
+        let config = ColorProps {
+            colors: HashMap::from([
+                ("#fff".to_string(), "background".to_string()),
+                ("blue".to_string(), "primaryColor".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let (colors, errors) = extract(
+            r##"<svg><path fill="#ffffff" /><path fill="#ffffff" /><path fill="red" /><path fill="Blue" /></svg>"##,
+            &config,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            colors,
+            props(&[
+                ("background", "#ffffff"),
+                ("secondaryColor", "red"),
+                ("primaryColor", "Blue"),
+            ])
+        );
+    }
+
+    #[test]
+    fn number_colors_past_the_names() {
+        let (colors, _) = extract(
+            r##"<svg><path fill="#000" /><path fill="#111" /><path fill="#222" /><path fill="#333" /><path fill="#444" /></svg>"##,
+            &ColorProps::default(),
+        );
+
+        assert_eq!(
+            colors,
+            props(&[
+                ("primaryColor", "#000"),
+                ("secondaryColor", "#111"),
+                ("tertiaryColor", "#222"),
+                ("color4", "#333"),
+                ("color5", "#444"),
+            ])
+        );
+    }
+
+    #[test]
+    fn report_invalid_names() {
+        let config = ColorProps {
+            names: vec!["title".to_string(), "accent".to_string()],
+            colors: HashMap::from([("#fff".to_string(), "brand-color".to_string())]),
+        };
+
+        let (colors, errors) =
+            extract(r##"<svg><path fill="#fff" stroke="red" /></svg>"##, &config);
+
+        assert_eq!(colors, props(&[("accent", "#fff"), ("color2", "red")]));
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| (err.path.as_str(), err.value.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("colorProps.colors.#fff", Some("\"brand-color\"")),
+                ("colorProps.names[0]", Some("\"title\"")),
+            ]
+        );
+    }
+}
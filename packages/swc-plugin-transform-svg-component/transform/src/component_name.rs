@@ -0,0 +1,110 @@
+/// Used when neither `state.componentName` nor a filename is available.
+pub const DEFAULT_COMPONENT_NAME: &str = "SvgComponent";
+
+/// Extensions removed from the end of a filename, so `icon.svg.tsx` becomes `icon`.
+const EXTENSIONS: [&str; 7] = ["svg", "js", "jsx", "ts", "tsx", "mjs", "cjs"];
+
+/// Bindings the generated module may import next to the component. JavaScript
+/// reserved words need no check: they are lowercase, while every derived name
+/// contains an uppercase letter after the prefix.
+const RESERVED: [&str; 30] = [
+    "React", "ForwardRef", "Memo", "Ref", "SVGProps", "SVGRProps", "SvgProps", "Svg", "Circle", "ClipPath", "Ellipse", "G",
+    "LinearGradient", "RadialGradient", "Line", "Path", "Pattern", "Polygon", "Polyline", "Rect",
+    "Symbol", "Text", "TextPath", "TSpan", "Use", "Defs", "Stop", "Mask", "Image",
+    "ForeignObject",
+];
+
+/// Derives a component name from a file path, e.g. `icons/arrow-left.svg`
+/// becomes `SvgArrowLeft` with the default `Svg` prefix.
+pub fn component_name_from_path(path: &str, prefix: &str, suffix: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let mut stem = file_name;
+    while let Some((rest, ext)) = stem.rsplit_once('.') {
+        if rest.is_empty() || !EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) {
+            break;
+        }
+        stem = rest;
+    }
+
+    let pascal_case = stem
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+
+    if pascal_case.is_empty() {
+        return DEFAULT_COMPONENT_NAME.to_string();
+    }
+
+    let name = format!(
+        "{}{}{}",
+        sanitize(prefix),
+        pascal_case,
+        sanitize(suffix)
+    );
+    if name.starts_with(|c: char| c.is_ascii_digit()) || RESERVED.contains(&name.as_str()) {
+        format!("Svg{}", name)
+    } else {
+        name
+    }
+}
+
+/// Keeps only the characters allowed in an identifier.
+fn sanitize(affix: &str) -> String {
+    affix
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_pascal_case_name() {
+        assert_eq!(
+            component_name_from_path("src/icons/arrow-left.svg", "Svg", ""),
+            "SvgArrowLeft"
+        );
+        assert_eq!(
+            component_name_from_path("C:\\icons\\arrow_left_24.svg.tsx", "Svg", ""),
+            "SvgArrowLeft24"
+        );
+        assert_eq!(
+            component_name_from_path("icons/close.min.svg", "Svg", ""),
+            "SvgCloseMin"
+        );
+    }
+
+    #[test]
+    fn apply_prefix_and_suffix() {
+        assert_eq!(
+            component_name_from_path("arrow-left.svg", "", "Icon"),
+            "ArrowLeftIcon"
+        );
+        assert_eq!(
+            component_name_from_path("arrow-left.svg", "My-", "Icon"),
+            "MyArrowLeftIcon"
+        );
+    }
+
+    #[test]
+    fn avoid_invalid_identifiers() {
+        assert_eq!(component_name_from_path("24-hours.svg", "", ""), "Svg24Hours");
+        assert_eq!(component_name_from_path("react.svg", "", ""), "SvgReact");
+        assert_eq!(component_name_from_path("path.svg", "", ""), "SvgPath");
+        assert_eq!(component_name_from_path("ref.svg", "", ""), "SvgRef");
+        assert_eq!(component_name_from_path("SVGRProps.svg", "", ""), "SvgSVGRProps");
+        assert_eq!(
+            component_name_from_path("---.svg", "Svg", ""),
+            DEFAULT_COMPONENT_NAME
+        );
+    }
+}
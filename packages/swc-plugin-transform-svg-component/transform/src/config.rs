@@ -1,13 +1,17 @@
-use serde::{Deserialize, Deserializer,};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
+use std::collections::HashMap;
 
-#[derive(Deserialize, PartialEq)]
+use crate::component_name::{component_name_from_path, DEFAULT_COMPONENT_NAME};
+
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ExportType {
     Named,
     Default,
 }
 
+#[derive(Clone)]
 pub enum ExpandProps {
     Start,
     End,
@@ -32,14 +36,27 @@ impl<'de> Deserialize<'de> for ExpandProps {
   }
 }
 
-#[derive(Deserialize)]
+impl Serialize for ExpandProps {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+      S: Serializer,
+  {
+      match self {
+          ExpandProps::Start => serializer.serialize_str("start"),
+          ExpandProps::End => serializer.serialize_str("end"),
+          ExpandProps::Boolean(b) => serializer.serialize_bool(*b),
+      }
+  }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub enum JSXRuntime {
     Automatic,
     Classic,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JSXRuntimeImport {
     pub source: String,
@@ -48,21 +65,44 @@ pub struct JSXRuntimeImport {
     pub specifiers: Option<Vec<String>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Caller {
     pub previous_export: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct State {
-    pub component_name: String,
+    /// Derived from the filename by `Config::resolve_component_name` when unset.
+    #[serde(default)]
+    pub component_name: Option<String>,
     #[serde(default = "default_state_caller")]
     pub caller: Caller,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorProps {
+    /// Names of the props, given to the colors from the most to the least
+    /// used. Colors past the end of the list are named `color4`, `color5`, ...
+    #[serde(default = "default_color_prop_names")]
+    pub names: Vec<String>,
+    /// Prop names of specific colors, taking precedence over `names`.
+    #[serde(default)]
+    pub colors: HashMap<String, String>,
+}
+
+impl Default for ColorProps {
+    fn default() -> Self {
+        ColorProps {
+            names: default_color_prop_names(),
+            colors: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     #[serde(default = "false_by_default")]
@@ -90,6 +130,40 @@ pub struct Config {
     pub jsx_runtime_import: JSXRuntimeImport,
     #[serde(default = "default_import_source")]
     pub import_source: String,
+    #[serde(default = "default_component_name_prefix")]
+    pub component_name_prefix: String,
+    #[serde(default)]
+    pub component_name_suffix: String,
+    /// Makes the ids of the SVG unique to each instance of the component,
+    /// with React's `useId`.
+    #[serde(default = "false_by_default")]
+    pub use_id: bool,
+    /// Turns the colors of the SVG into props defaulting to them.
+    #[serde(default)]
+    pub color_props: Option<ColorProps>,
+}
+
+impl Config {
+  /// Fills in `state.componentName` from the file being transformed when the
+  /// caller did not pass one.
+  pub fn resolve_component_name(&mut self, filename: Option<&str>) {
+      if self.state.component_name.is_none() {
+          self.state.component_name = filename.map(|filename| {
+              component_name_from_path(
+                  filename,
+                  &self.component_name_prefix,
+                  &self.component_name_suffix,
+              )
+          });
+      }
+  }
+
+  pub fn component_name(&self) -> &str {
+      self.state
+          .component_name
+          .as_deref()
+          .unwrap_or(DEFAULT_COMPONENT_NAME)
+  }
 }
 
 // fn true_by_default() -> bool {
@@ -108,13 +182,17 @@ pub fn default_named_export() -> String {
   "ReactComponent".to_string()
 }
 
+pub fn default_component_name_prefix() -> String {
+  "Svg".to_string()
+}
+
 pub fn default_export_type() -> ExportType {
   ExportType::Default
 }
 
 pub fn default_state() -> State {
   State {
-      component_name: "SvgComponent".to_string(),
+      component_name: None,
       caller: Caller {
           previous_export: "".to_string(),
       },
@@ -125,6 +203,12 @@ pub fn default_state_caller() -> Caller {
   Caller { previous_export: "".into() }
 }
 
+pub fn default_color_prop_names() -> Vec<String> {
+  ["primaryColor", "secondaryColor", "tertiaryColor"]
+      .map(String::from)
+      .to_vec()
+}
+
 pub fn default_expand_props() -> ExpandProps {
   ExpandProps::End
 }
@@ -0,0 +1,94 @@
+use std::fmt;
+use swc_core::common::errors::HANDLER;
+
+/// A recoverable problem caused by the plugin configuration.
+///
+/// Visitors collect these instead of panicking so that a single bad value does
+/// not abort the whole transform. The plugin entry points report them through
+/// SWC's diagnostic handler with [`ConfigError::emit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// Path of the offending key, e.g. `add_jsx_attribute.attributes[0].value`.
+    pub path: String,
+    pub message: String,
+    pub value: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl ConfigError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        ConfigError {
+            path: path.into(),
+            message: message.into(),
+            value: None,
+            hint: None,
+        }
+    }
+
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Prefixes the key path with the name of the enclosing config section.
+    pub fn nested(mut self, section: &str) -> Self {
+        self.path = if self.path.is_empty() {
+            section.to_string()
+        } else if self.path.starts_with('[') {
+            format!("{}{}", section, self.path)
+        } else {
+            format!("{}.{}", section, self.path)
+        };
+        self
+    }
+
+    /// Reports the error through the current SWC diagnostic handler. Returns
+    /// `false` when no handler is installed, leaving the error to the caller.
+    pub fn emit(&self) -> bool {
+        if !HANDLER.is_set() {
+            return false;
+        }
+        HANDLER.with(|handler| {
+            let mut diagnostic = handler.struct_err(&self.to_string());
+            if let Some(value) = &self.value {
+                diagnostic.note(&format!("found value: {}", value));
+            }
+            if let Some(hint) = &self.hint {
+                diagnostic.help(hint);
+            }
+            diagnostic.emit();
+        });
+        true
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Deserializes a plugin config, reporting the key path of the first invalid
+/// value on failure.
+pub fn parse_config<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, ConfigError> {
+    let deserializer = &mut serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        ConfigError::new(
+            if path == "." { String::new() } else { path },
+            err.into_inner().to_string(),
+        )
+        .with_hint("check the plugin options against the documented config shape")
+    })
+}
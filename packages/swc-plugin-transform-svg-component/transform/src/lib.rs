@@ -1,6 +1,6 @@
-use crate::config::*;
+use crate::{config::*, error::ConfigError};
 use swc_core::{
-    common::{input::StringInput, BytePos, DUMMY_SP},
+    common::{input::StringInput, util::take::Take, BytePos, DUMMY_SP},
     ecma::{
         ast::*,
         parser::{EsConfig, Parser, Syntax},
@@ -9,26 +9,57 @@ use swc_core::{
     quote,
 };
 
+pub mod color_props;
+pub mod component_name;
 pub mod config;
+pub mod error;
+pub mod references;
+pub mod use_id;
 
 pub struct TransformSVGComponentVisitor {
     pub config: Config,
+    /// Config problems found while transforming, keyed by the path of their
+    /// option, e.g. `namedExport`. `swc-plugin-svgr` nests them under the
+    /// `transform_svg_component` section.
+    pub errors: Vec<ConfigError>,
     jsx_svg_element: Option<JSXElement>,
+    /// Whether the component calls `useId()`, i.e. `useId` is set and the
+    /// SVG defines ids.
+    calls_use_id: bool,
+    /// The props the colors of the SVG were turned into, when `colorProps` is set.
+    color_props: Vec<color_props::ColorProp>,
 }
 
 impl VisitMut for TransformSVGComponentVisitor {
     fn visit_mut_jsx_element(&mut self, jsx_element: &mut JSXElement) {
-        jsx_element.visit_mut_children_with(self);
         if let JSXElementName::Ident(tag_name) = &jsx_element.opening.name {
             if *tag_name.sym == *"svg" || *tag_name.sym == *"Svg" {
-                self.jsx_svg_element = Some(jsx_element.clone());
+                // The module body is rebuilt from scratch, so the element can be
+                // moved out instead of cloned. Nested svg elements move along with it.
+                self.jsx_svg_element = Some(jsx_element.take());
+                return;
             }
         }
+        jsx_element.visit_mut_children_with(self);
     }
 
     fn visit_mut_module(&mut self, module: &mut Module) {
         module.visit_mut_children_with(self);
 
+        if let Some(config) = &self.config.color_props {
+            if let Some(jsx_element) = self.jsx_svg_element.as_mut() {
+                let (props, mut errors) = color_props::extract_colors(jsx_element, config);
+                self.color_props = props;
+                self.errors.append(&mut errors);
+            }
+        }
+
+        if self.config.use_id {
+            if let Some(jsx_element) = self.jsx_svg_element.as_mut() {
+                self.calls_use_id = use_id::rewrite_ids(jsx_element);
+            }
+        }
+
         let mut new_items: Vec<ModuleItem> = vec![];
         let mut import_stmts = self.create_imports();
         let mut export_stmts = self.create_exports();
@@ -49,7 +80,10 @@ impl TransformSVGComponentVisitor {
     pub fn new(config: Config) -> Self {
         TransformSVGComponentVisitor {
             config,
+            errors: vec![],
             jsx_svg_element: None,
+            calls_use_id: false,
+            color_props: vec![],
         }
     }
 
@@ -57,9 +91,10 @@ impl TransformSVGComponentVisitor {
         let mut imports: Vec<ImportDecl> = Vec::new();
         match self.config.jsx_runtime {
             Some(JSXRuntime::Automatic) => {}
-            _ => {
-                imports.push(get_jsx_runtime_import(&self.config.jsx_runtime_import));
-            }
+            _ => match get_jsx_runtime_import(&self.config.jsx_runtime_import) {
+                Ok(import) => imports.push(import),
+                Err(err) => self.errors.push(err),
+            },
         };
 
         if self.config.native {
@@ -124,6 +159,17 @@ impl TransformSVGComponentVisitor {
                 }));
         }
 
+        if self.calls_use_id {
+            get_or_create_import(&mut imports, &(self.config.import_source))
+                .specifiers
+                .push(ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: Ident::new("useId".into(), DUMMY_SP),
+                    imported: None,
+                    is_type_only: false,
+                }));
+        }
+
         imports
             .into_iter()
             .map(|decl| ModuleItem::ModuleDecl(ModuleDecl::Import(decl)))
@@ -132,7 +178,7 @@ impl TransformSVGComponentVisitor {
 
     fn create_exports(&mut self) -> Vec<ModuleItem> {
         let mut exports: Vec<ModuleItem> = Vec::new();
-        let mut export_identifier = self.config.state.component_name.clone();
+        let mut export_identifier = self.config.component_name().to_string();
 
         if self.config.forward_ref {
             let stmt = quote!(
@@ -151,13 +197,19 @@ impl TransformSVGComponentVisitor {
             export_identifier = "Memo".to_string();
         }
 
-        if self.config.export_type == ExportType::Named
-            || !self.config.state.caller.previous_export.is_empty()
-        {
-            if self.config.named_export.is_empty() {
-                panic!("\"namedExport\" not specified");
-            }
+        let mut named_export = self.config.export_type == ExportType::Named
+            || !self.config.state.caller.previous_export.is_empty();
+
+        if named_export && self.config.named_export.is_empty() {
+            self.errors.push(
+                ConfigError::new("namedExport", "\"namedExport\" not specified")
+                    .with_value("\"\"")
+                    .with_hint("set \"namedExport\" or use `exportType: \"default\"`; falling back to a default export"),
+            );
+            named_export = false;
+        }
 
+        if named_export {
             exports.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
                 NamedExport {
                     span: DUMMY_SP,
@@ -200,11 +252,17 @@ impl TransformSVGComponentVisitor {
                     None,
                 );
 
-                let mut ast = parser
-                    .parse_module()
-                    .expect("Failed to parse \"previousExport\"");
-
-                exports.append(&mut ast.body)
+                match parser.parse_module() {
+                    Ok(mut ast) => exports.append(&mut ast.body),
+                    Err(_) => self.errors.push(
+                        ConfigError::new(
+                            "state.caller.previousExport",
+                            "failed to parse \"previousExport\"",
+                        )
+                        .with_value(self.config.state.caller.previous_export.clone())
+                        .with_hint("\"previousExport\" must be valid JavaScript module code"),
+                    ),
+                }
             }
         } else {
             exports.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
@@ -219,8 +277,29 @@ impl TransformSVGComponentVisitor {
 
     fn create_component_body(&mut self) -> Option<Vec<ModuleItem>> {
         let (component_props, interfaces) = self.create_component_props();
-        match &self.jsx_svg_element {
+        match self.jsx_svg_element.take() {
             Some(jsx_element) => {
+                let jsx_expr = Box::new(Expr::JSXElement(Box::new(jsx_element)));
+                let body = if self.calls_use_id {
+                    BlockStmtOrExpr::BlockStmt(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts: vec![
+                            // `useId()` gives ids like `:r0:`, which are not valid
+                            // in CSS selectors.
+                            quote!(
+                                "const $id = useId().replace(/:/g, \"\");" as Stmt,
+                                id = Ident::new(use_id::ID_VARIABLE.into(), DUMMY_SP)
+                            ),
+                            Stmt::Return(ReturnStmt {
+                                span: DUMMY_SP,
+                                arg: Some(jsx_expr),
+                            }),
+                        ],
+                    })
+                } else {
+                    BlockStmtOrExpr::Expr(jsx_expr)
+                };
+
                 let component_decl = VarDecl {
                     span: DUMMY_SP,
                     kind: VarDeclKind::Const,
@@ -229,7 +308,7 @@ impl TransformSVGComponentVisitor {
                         span: DUMMY_SP,
                         name: Pat::Ident(BindingIdent {
                             id: Ident::new(
-                                self.config.state.component_name.clone().into(),
+                                self.config.component_name().into(),
                                 DUMMY_SP,
                             ),
                             type_ann: None,
@@ -237,9 +316,7 @@ impl TransformSVGComponentVisitor {
                         init: Some(Box::new(Expr::Arrow(ArrowExpr {
                             span: DUMMY_SP,
                             params: component_props,
-                            body: Box::new(BlockStmtOrExpr::Expr(Box::new(Expr::JSXElement(
-                                Box::new(jsx_element.clone()),
-                            )))),
+                            body: Box::new(body),
                             is_async: false,
                             is_generator: false,
                             type_params: None,
@@ -250,10 +327,8 @@ impl TransformSVGComponentVisitor {
                 };
 
                 let mut body_stmts: Vec<ModuleItem> = interfaces
-                    .iter()
-                    .map(|interface| {
-                        ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(Box::new(interface.clone()))))
-                    })
+                    .into_iter()
+                    .map(|interface| ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(Box::new(interface)))))
                     .collect();
 
                 body_stmts.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(
@@ -271,7 +346,7 @@ impl TransformSVGComponentVisitor {
         let mut properties: Vec<ObjectPatProp> = vec![];
         let mut interfaces: Vec<TsInterfaceDecl> = vec![];
         let mut property_signatures: Vec<TsTypeElement> = vec![];
-        if self.config.title_prop || self.config.desc_prop {
+        if self.config.title_prop || self.config.desc_prop || !self.color_props.is_empty() {
             if self.config.title_prop {
                 properties.push(create_object_assign_property("title"));
                 properties.push(create_object_assign_property("titleId"));
@@ -292,6 +367,22 @@ impl TransformSVGComponentVisitor {
                 }
             }
 
+            for color_prop in &self.color_props {
+                properties.push(ObjectPatProp::Assign(AssignPatProp {
+                    span: DUMMY_SP,
+                    key: Ident::new(color_prop.name.as_str().into(), DUMMY_SP),
+                    value: Some(Box::new(Expr::Lit(Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: color_prop.default.as_str().into(),
+                        raw: None,
+                    })))),
+                }));
+
+                if self.config.typescript {
+                    property_signatures.push(create_property_signature(&color_prop.name));
+                }
+            }
+
             let mut prop = ObjectPat {
                 span: DUMMY_SP,
                 props: properties,
@@ -401,7 +492,7 @@ impl TransformSVGComponentVisitor {
     }
 }
 
-fn get_jsx_runtime_import(cfg: &JSXRuntimeImport) -> ImportDecl {
+fn get_jsx_runtime_import(cfg: &JSXRuntimeImport) -> Result<ImportDecl, ConfigError> {
     let mut specifiers: Vec<ImportSpecifier> = Vec::new();
     if let Some(namespace) = &cfg.namespace {
         specifiers.push(ImportSpecifier::Namespace(ImportStarAsSpecifier {
@@ -423,10 +514,15 @@ fn get_jsx_runtime_import(cfg: &JSXRuntimeImport) -> ImportDecl {
             }));
         }
     } else {
-        panic!("Specify \"namespace\", \"defaultSpecifier\", or \"specifiers\" in \"jsxRuntimeImport\" option");
+        return Err(ConfigError::new(
+            "jsxRuntimeImport",
+            "no import specifier given for the JSX runtime",
+        )
+        .with_value(format!("{{ source: \"{}\" }}", cfg.source))
+        .with_hint("specify \"namespace\", \"defaultSpecifier\", or \"specifiers\" in \"jsxRuntimeImport\" option"));
     }
 
-    ImportDecl {
+    Ok(ImportDecl {
         span: DUMMY_SP,
         specifiers,
         src: Box::new(Str {
@@ -436,7 +532,7 @@ fn get_jsx_runtime_import(cfg: &JSXRuntimeImport) -> ImportDecl {
         }),
         type_only: false,
         asserts: None,
-    }
+    })
 }
 
 fn get_or_create_import<'a>(imports: &'a mut Vec<ImportDecl>, source: &str) -> &'a mut ImportDecl {
@@ -525,3 +621,111 @@ fn create_svg_svg_element_type_param() -> Option<Box<TsTypeParamInstantiation>>
         }))],
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use swc_core::ecma::{transforms::testing::test, visit::as_folder};
+
+    fn transform_errors(config: serde_json::Value) -> Vec<ConfigError> {
+        let mut parser = Parser::new(
+            Syntax::Es(EsConfig {
+                jsx: true,
+                ..Default::default()
+            }),
+            StringInput::new("<svg />;", BytePos(0), BytePos(0)),
+            None,
+        );
+        let mut module = parser.parse_module().unwrap();
+        let mut visitor =
+            TransformSVGComponentVisitor::new(serde_json::from_value(config).unwrap());
+        module.visit_mut_with(&mut visitor);
+        visitor.errors
+    }
+
+    #[test]
+    fn collect_error_for_missing_named_export() {
+        let errors = transform_errors(json!({ "exportType": "named", "namedExport": "" }));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].clone().nested("transform_svg_component").path,
+            "transform_svg_component.namedExport"
+        );
+    }
+
+    #[test]
+    fn collect_error_for_jsx_runtime_import_without_specifier() {
+        let errors = transform_errors(json!({
+            "jsxRuntime": "classic",
+            "jsxRuntimeImport": { "source": "preact" }
+        }));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "jsxRuntimeImport");
+        assert_eq!(errors[0].value.as_deref(), Some("{ source: \"preact\" }"));
+    }
+
+    #[test]
+    fn collect_error_for_invalid_previous_export() {
+        let errors = transform_errors(json!({
+            "state": { "caller": { "previousExport": "export default {" } }
+        }));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "state.caller.previousExport");
+    }
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(TransformSVGComponentVisitor::new(
+            serde_json::from_value(json!({
+                "useId": true,
+                "state": { "componentName": "SvgStar" }
+            }))
+            .unwrap()
+        )),
+        call_use_id_without_colons,
+        r##"<svg><style>{"#a{fill:red}"}</style><path id="a" /></svg>;"##,
+        r##"import * as React from "react"; import { useId } from "react"; const SvgStar = (props)=>{ const id = useId().replace(/:/g, ""); return <svg><style>{`#${id}-a{fill:red}`}</style><path id={`${id}-a`} /></svg>; }; export default SvgStar;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(TransformSVGComponentVisitor::new(
+            serde_json::from_value(json!({
+                "useId": true,
+                "colorProps": {},
+                "state": { "componentName": "SvgStar" }
+            }))
+            .unwrap()
+        )),
+        prefix_ids_in_styles_with_color_props,
+        r##"<svg><linearGradient id="a" /><path style="fill:#fff;stroke:url(#a)" /></svg>;"##,
+        r##"import * as React from "react"; import { useId } from "react"; const SvgStar = ({ primaryColor = "#fff", ...props })=>{ const id = useId().replace(/:/g, ""); return <svg><linearGradient id={`${id}-a`} /><path style={`fill:${primaryColor};stroke:url(#${id}-a)`} /></svg>; }; export default SvgStar;"##
+    );
+
+    test!(
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        |_| as_folder(TransformSVGComponentVisitor::new(
+            serde_json::from_value(json!({
+                "useId": true,
+                "state": { "componentName": "SvgStar" }
+            }))
+            .unwrap()
+        )),
+        keep_aria_label_text_with_use_id,
+        r##"<svg aria-label="a" aria-labelledby="a"><title id="a">Star</title></svg>;"##,
+        r##"import * as React from "react"; import { useId } from "react"; const SvgStar = (props)=>{ const id = useId().replace(/:/g, ""); return <svg aria-label="a" aria-labelledby={`${id}-a`}><title id={`${id}-a`}>Star</title></svg>; }; export default SvgStar;"##
+    );
+}
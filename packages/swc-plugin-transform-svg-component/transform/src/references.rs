@@ -0,0 +1,144 @@
+//! Where SVG attribute values and style sheets refer to ids, shared by the
+//! transforms rewriting ids.
+
+use std::{iter, ops::Range};
+
+/// The aria attributes holding id references, lowercased without `-`. The
+/// others, like `aria-label`, hold text.
+const ARIA_ID_REFERENCES: [&str; 8] = [
+    "arialabelledby",
+    "ariadescribedby",
+    "ariacontrols",
+    "ariaowns",
+    "ariaflowto",
+    "ariaactivedescendant",
+    "ariadetails",
+    "ariaerrormessage",
+];
+
+/// Where the ids referenced by an attribute value are: `url(#id)` values,
+/// `href` and `xlink:href` attributes, the id lists of `aria-labelledby` and
+/// the other `ARIA_ID_REFERENCES`, and SMIL `begin` and `end` timing. `name`
+/// can be given in any case, with or without `-` and `:`, as JSX camelCases
+/// SVG attribute names. The `id` attribute itself refers to nothing.
+pub fn reference_spans(name: &str, value: &str) -> Vec<Range<usize>> {
+    let name = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+
+    match name.as_str() {
+        "id" => vec![],
+        "href" | "xlinkhref" => match value.strip_prefix('#') {
+            Some(id) if !id.is_empty() => iter::once(1..value.len()).collect(),
+            _ => vec![],
+        },
+        // `begin="a.end; b.click+1s"`.
+        "begin" | "end" => token_spans(value, |c| c == ';')
+            .filter_map(|span| {
+                let token = &value[span.clone()];
+                let id = &token[..token.find('.')?];
+                (!id.is_empty() && !id.starts_with(|c: char| c.is_ascii_digit() || c == '-'))
+                    .then(|| span.start..span.start + id.len())
+            })
+            .collect(),
+        _ if ARIA_ID_REFERENCES.contains(&name.as_str()) => token_spans(value, |c| c.is_ascii_whitespace()).collect(),
+        _ => url_spans(value),
+    }
+}
+
+/// Where the ids a style sheet refers to are: every `#name`, be it an id
+/// selector or in `url(#name)`. Hex colors are included, so callers should
+/// only consider the ids the document defines.
+pub fn style_reference_spans(css: &str) -> Vec<Range<usize>> {
+    css.match_indices('#')
+        .filter_map(|(index, _)| {
+            let start = index + 1;
+            let end = css[start..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .map_or(css.len(), |end| start + end);
+            (end > start).then_some(start..end)
+        })
+        .collect()
+}
+
+/// `value` with the text at each of `spans`, in order, replaced by `replace`.
+pub fn replace_spans(
+    value: &str,
+    spans: &[Range<usize>],
+    mut replace: impl FnMut(&str) -> String,
+) -> String {
+    let mut replaced = String::with_capacity(value.len());
+    let mut end = 0;
+    for span in spans {
+        replaced.push_str(&value[end..span.start]);
+        replaced.push_str(&replace(&value[span.clone()]));
+        end = span.end;
+    }
+    replaced.push_str(&value[end..]);
+    replaced
+}
+
+/// The spans of the `url(#id)` references in `value`, with or without quotes.
+fn url_spans(value: &str) -> Vec<Range<usize>> {
+    value
+        .match_indices("url(")
+        .filter_map(|(index, _)| {
+            let after = &value[index + 4..];
+            let reference = after.trim_start_matches([' ', '"', '\'']);
+            let start = value.len() - reference.strip_prefix('#')?.len();
+            let end = value[start..]
+                .find(|c: char| c == ')' || c == '"' || c == '\'' || c.is_ascii_whitespace())
+                .map_or(value.len(), |end| start + end);
+            (end > start).then_some(start..end)
+        })
+        .collect()
+}
+
+/// The spans of the trimmed, non-empty tokens of `value`, split at the ASCII
+/// `separator` characters.
+fn token_spans(
+    value: &str,
+    separator: fn(char) -> bool,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut offset = 0;
+    value.split(separator).filter_map(move |token| {
+        let start = offset + token.len() - token.trim_start().len();
+        let end = offset + token.trim_end().len();
+        offset += token.len() + 1;
+        (end > start).then_some(start..end)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references(spans: Vec<Range<usize>>, value: &str) -> Vec<&str> {
+        spans.into_iter().map(|span| &value[span]).collect()
+    }
+
+    #[test]
+    fn find_references() {
+        let cases: [(&str, &str, &[&str]); 11] = [
+            ("fill", "url(#a)", &["a"]),
+            ("style", "fill: url( '#b' ); stroke: url(c)", &["b"]),
+            ("xlinkHref", "#p", &["p"]),
+            ("xlink:href", "#p", &["p"]),
+            ("href", "https://example.com/#p", &[]),
+            ("aria-labelledby", " title  desc ", &["title", "desc"]),
+            ("ariaActivedescendant", "item", &["item"]),
+            ("aria-label", "title", &[]),
+            ("begin", "0s; a.end+1s;b.click", &["a", "b"]),
+            ("end", "1.5s", &[]),
+            ("id", "a", &[]),
+        ];
+        for (name, value, expected) in cases {
+            assert_eq!(references(reference_spans(name, value), value), expected);
+        }
+
+        let css = ".a{fill:url(#g)} #x{}";
+        assert_eq!(references(style_reference_spans(css), css), ["g", "x"]);
+    }
+}
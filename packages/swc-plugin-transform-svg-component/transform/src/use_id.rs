@@ -0,0 +1,211 @@
+use std::{collections::HashSet, iter, ops::Range};
+use swc_core::{common::DUMMY_SP, ecma::ast::*};
+
+use crate::references::{reference_spans, style_reference_spans};
+
+/// Name of the variable the component stores the result of `useId()` in.
+pub const ID_VARIABLE: &str = "id";
+
+/// Rewrites the ids defined in `jsx_element` and the references to them to
+/// template literals based on the `useId()` result, so that every instance
+/// of the component gets its own ids: `id="a"` becomes ``id={`${id}-a`}``
+/// and `fill="url(#a)"` becomes ``fill={`url(#${id}-a)`}``.
+///
+/// Returns whether `jsx_element` defines any id, i.e. whether the component
+/// has to call `useId()`.
+pub fn rewrite_ids(jsx_element: &mut JSXElement) -> bool {
+    let mut ids = HashSet::new();
+    collect_ids(jsx_element, &mut ids);
+    if ids.is_empty() {
+        return false;
+    }
+
+    rewrite(jsx_element, &ids);
+    true
+}
+
+fn collect_ids(jsx_element: &JSXElement, ids: &mut HashSet<String>) {
+    for attr in &jsx_element.opening.attrs {
+        if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(Ident { sym, .. }),
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. }))),
+            ..
+        }) = attr
+        {
+            if &**sym == "id" {
+                ids.insert(value.to_string());
+            }
+        }
+    }
+
+    for child in &jsx_element.children {
+        if let JSXElementChild::JSXElement(child) = child {
+            collect_ids(child, ids);
+        }
+    }
+}
+
+fn rewrite(jsx_element: &mut JSXElement, ids: &HashSet<String>) {
+    for attr in jsx_element.opening.attrs.iter_mut() {
+        let JSXAttrOrSpread::JSXAttr(JSXAttr { name, value, .. }) = attr else {
+            continue;
+        };
+        let name = match name {
+            JSXAttrName::Ident(Ident { sym, .. }) => sym.to_string(),
+            JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+                format!("{}:{}", ns.sym, name.sym)
+            }
+        };
+
+        match value {
+            Some(JSXAttrValue::Lit(Lit::Str(Str { value: string, .. }))) => {
+                let spans = if name == "id" {
+                    iter::once(0..string.len()).collect()
+                } else {
+                    reference_spans(&name, string)
+                };
+                if let Some(tpl) = id_template(string, spans, ids) {
+                    *value = Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        span: DUMMY_SP,
+                        expr: JSXExpr::Expr(Box::new(Expr::Tpl(tpl))),
+                    }));
+                }
+            }
+            // Style objects, as SVGR gives them, and the template literals
+            // `colorProps` turns style strings into.
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                expr: JSXExpr::Expr(expr),
+                ..
+            })) if name == "style" => match &mut **expr {
+                Expr::Object(ObjectLit { props, .. }) => {
+                    for prop in props.iter_mut() {
+                        if let PropOrSpread::Prop(prop) = prop {
+                            if let Prop::KeyValue(KeyValueProp { value, .. }) = &mut **prop {
+                                if let Expr::Lit(Lit::Str(Str { value: string, .. })) = &**value {
+                                    let spans = reference_spans(&name, string);
+                                    if let Some(tpl) = id_template(string, spans, ids) {
+                                        **value = Expr::Tpl(tpl);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Expr::Tpl(tpl) => rewrite_template(tpl, &name, ids),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let is_style = matches!(
+        &jsx_element.opening.name,
+        JSXElementName::Ident(Ident { sym, .. }) if (**sym).eq_ignore_ascii_case("style")
+    );
+    for child in jsx_element.children.iter_mut() {
+        match child {
+            JSXElementChild::JSXElement(child) => rewrite(child, ids),
+            _ if is_style => rewrite_style(child, ids),
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites the text of a `<style>` child, given as JSX text or a string.
+fn rewrite_style(child: &mut JSXElementChild, ids: &HashSet<String>) {
+    let css = match child {
+        JSXElementChild::JSXText(JSXText { value, .. }) => value.to_string(),
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        }) => match &**expr {
+            Expr::Lit(Lit::Str(Str { value, .. })) => value.to_string(),
+            _ => return,
+        },
+        _ => return,
+    };
+
+    if let Some(tpl) = id_template(&css, style_reference_spans(&css), ids) {
+        *child = JSXElementChild::JSXExprContainer(JSXExprContainer {
+            span: DUMMY_SP,
+            expr: JSXExpr::Expr(Box::new(Expr::Tpl(tpl))),
+        });
+    }
+}
+
+/// Prefixes the ids referenced by the text parts of `tpl`, the value of the
+/// attribute `name`. References never span an expression, as `colorProps`
+/// only puts colors there.
+fn rewrite_template(tpl: &mut Tpl, name: &str, ids: &HashSet<String>) {
+    let mut quasis = vec![];
+    let mut exprs = vec![];
+    let mut original_exprs = std::mem::take(&mut tpl.exprs).into_iter();
+    for quasi in std::mem::take(&mut tpl.quasis) {
+        let text = quasi.cooked.as_deref().unwrap_or_default().to_string();
+        match id_template(&text, reference_spans(name, &text), ids) {
+            Some(parts) => {
+                quasis.extend(parts.quasis);
+                exprs.extend(parts.exprs);
+            }
+            None => quasis.push(quasi),
+        }
+        exprs.extend(original_exprs.next());
+    }
+
+    let last = quasis.len() - 1;
+    for (index, quasi) in quasis.iter_mut().enumerate() {
+        quasi.tail = index == last;
+    }
+    tpl.quasis = quasis;
+    tpl.exprs = exprs;
+}
+
+/// A template literal of `value` where the ids at `spans` are prefixed with
+/// the `useId()` result, or `None` when none of them is one of `ids`.
+fn id_template(value: &str, spans: Vec<Range<usize>>, ids: &HashSet<String>) -> Option<Tpl> {
+    let spans = spans
+        .into_iter()
+        .filter(|span| ids.contains(&value[span.clone()]))
+        .collect::<Vec<_>>();
+    if spans.is_empty() {
+        return None;
+    }
+
+    let mut quasis = vec![];
+    let mut exprs = vec![];
+    let mut quasi = String::new();
+    let mut end = 0;
+    for span in spans {
+        quasi.push_str(&value[end..span.start]);
+        quasis.push(template_element(std::mem::take(&mut quasi), false));
+        exprs.push(Box::new(Expr::Ident(Ident::new(
+            ID_VARIABLE.into(),
+            DUMMY_SP,
+        ))));
+        quasi.push('-');
+        quasi.push_str(&value[span.clone()]);
+        end = span.end;
+    }
+    quasi.push_str(&value[end..]);
+    quasis.push(template_element(quasi, true));
+
+    Some(Tpl {
+        span: DUMMY_SP,
+        exprs,
+        quasis,
+    })
+}
+
+/// A template literal part reading `cooked`.
+pub fn template_element(cooked: String, tail: bool) -> TplElement {
+    let raw = cooked
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+    TplElement {
+        span: DUMMY_SP,
+        tail,
+        cooked: Some(cooked.into()),
+        raw: raw.into(),
+    }
+}